@@ -0,0 +1,585 @@
+//! The module for systemd calendar events, as used by the `OnCalendar=` setting of timers
+//!
+//! A calendar event has the form `[WEEKDAYS] [YEAR-]MONTH-DAY [HOUR:MINUTE[:SECOND]]`, see
+//! systemd.time(7). Weekdays are given by name, as lists (`Sat,Sun`) or ranges (`Mon..Fri`). The
+//! other components take `*`, values, lists, ranges (`1..5`) and repetitions (`0/15`). A `~`
+//! instead of the last `-` counts the day from the end of the month, so `*-02~01` is the last day
+//! of February.
+//!
+//! A missing date means every day, a missing time means midnight and missing seconds mean `00`.
+//! The shorthands `minutely`, `hourly`, `daily`, `weekly`, `monthly`, `yearly`, `annually`,
+//! `quarterly` and `semiannually` are supported as well. Time zones are not, as kairos works on
+//! naive moments.
+//!
+//! `Display` prints the normalized form, like `systemd-analyze calendar` does:
+//! `mon,tue,wed 9:0` becomes `Mon..Wed *-*-* 09:00:00`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Timelike;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::util::days_in_month;
+use crate::util::parse_error;
+use crate::util::split_tokens;
+use crate::util::Schedule;
+use crate::util::ScheduleIter;
+use crate::util::MAX_DAYS_WITHOUT_OCCURRENCE;
+
+const WEEKDAY_NAMES: [(&str, &str); 7] = [
+    ("Mon", "Monday"),
+    ("Tue", "Tuesday"),
+    ("Wed", "Wednesday"),
+    ("Thu", "Thursday"),
+    ("Fri", "Friday"),
+    ("Sat", "Saturday"),
+    ("Sun", "Sunday"),
+];
+
+// A value, a range of values or a repetition of a value in one component of the event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    start: u32,
+    stop: Option<u32>,
+    repeat: Option<u32>,
+}
+
+impl Item {
+    fn matches(&self, value: u32) -> bool {
+        let in_range = match self.stop {
+            Some(stop) => (self.start..=stop).contains(&value),
+            None => value == self.start || (self.repeat.is_some() && value > self.start),
+        };
+        in_range && (value - self.start).checked_rem(self.repeat.unwrap_or(1)) == Some(0)
+    }
+
+    // Match a day counted from the end of the month, where repetitions run towards the end
+    fn matches_from_end(&self, value: u32) -> bool {
+        match (self.stop, self.repeat) {
+            (None, Some(r)) => value <= self.start && (self.start - value).checked_rem(r) == Some(0),
+            _ => self.matches(value),
+        }
+    }
+}
+
+// A component of the event, `None` being `*`
+type Component = Option<Vec<Item>>;
+
+fn component_matches(component: &Component, value: u32) -> bool {
+    match component {
+        Some(items) => items.iter().any(|item| item.matches(value)),
+        None => true,
+    }
+}
+
+/// A parsed calendar event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    /// Bit 0 is Monday, no bits set means every day of the week
+    weekdays: u8,
+    year: Component,
+    month: Component,
+    day: Component,
+    /// The day counts from the end of the month
+    end_of_month: bool,
+    hour: Component,
+    minute: Component,
+    second: Component,
+}
+
+impl CalendarEvent {
+    /// Check whether `moment` matches the event
+    pub fn matches(&self, moment: &NaiveDateTime) -> bool {
+        self.matches_date(moment.date())
+            && component_matches(&self.hour, moment.hour())
+            && component_matches(&self.minute, moment.minute())
+            && component_matches(&self.second, moment.second())
+    }
+
+    /// Find the first match at or after `moment`
+    ///
+    /// Returns `None` if the event does not happen within 400 years.
+    pub fn next_from(&self, moment: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let values = |component: &Component, max: u32| {
+            (0..=max)
+                .filter(|v| component_matches(component, *v))
+                .collect::<Vec<_>>()
+        };
+        let (hours, minutes, seconds) = (
+            values(&self.hour, 23),
+            values(&self.minute, 59),
+            values(&self.second, 59),
+        );
+
+        let mut date = moment.date();
+        let mut from = moment.time().with_nanosecond(0).unwrap_or(NaiveTime::MIN);
+        let limit = date
+            .checked_add_signed(Duration::days(MAX_DAYS_WITHOUT_OCCURRENCE))
+            .unwrap_or(NaiveDate::MAX);
+
+        while date <= limit {
+            if self.matches_date(date) {
+                let time = hours
+                    .iter()
+                    .flat_map(|h| minutes.iter().map(move |m| (*h, *m)))
+                    .flat_map(|(h, m)| seconds.iter().map(move |s| (h, m, *s)))
+                    .filter_map(|(h, m, s)| NaiveTime::from_hms_opt(h, m, s))
+                    .find(|time| *time >= from);
+
+                if let Some(time) = time {
+                    return Some(date.and_time(time));
+                }
+            }
+
+            date = date.succ_opt()?;
+            from = NaiveTime::MIN;
+        }
+
+        None
+    }
+
+    /// Generate the matches of the event, starting at `start`
+    pub fn occurrences(&self, start: NaiveDateTime) -> CalendarEventIter {
+        ScheduleIter::new(self.clone(), start)
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let day_matches = if self.end_of_month {
+            let days = days_in_month(date);
+            let from_end = days + 1 - date.day();
+            match &self.day {
+                Some(items) => items.iter().any(|item| item.matches_from_end(from_end)),
+                None => true,
+            }
+        } else {
+            component_matches(&self.day, date.day())
+        };
+
+        (self.weekdays == 0 || self.weekdays & (1 << date.weekday().num_days_from_monday()) != 0)
+            && u32::try_from(date.year()).is_ok_and(|y| component_matches(&self.year, y))
+            && component_matches(&self.month, date.month())
+            && day_matches
+    }
+}
+
+impl FromStr for CalendarEvent {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<CalendarEvent> {
+        if let Some(expanded) = expand_shorthand(s.trim()) {
+            return expanded.parse();
+        }
+
+        let mut event = CalendarEvent {
+            weekdays: 0,
+            year: None,
+            month: None,
+            day: None,
+            end_of_month: false,
+            hour: Some(vec![Item::value(0)]),
+            minute: Some(vec![Item::value(0)]),
+            second: Some(vec![Item::value(0)]),
+        };
+
+        let (mut had_date, mut had_time) = (false, false);
+        for (i, (position, token)) in split_tokens(s).into_iter().enumerate() {
+            if token.contains(':') && !had_time {
+                had_time = true;
+                parse_time(&mut event, position, token)?;
+            } else if (token.contains('-') || token.contains('~')) && !had_date && !had_time && !is_weekdays(token) {
+                had_date = true;
+                parse_date(&mut event, position, token)?;
+            } else if i == 0 {
+                event.weekdays = parse_weekdays(position, token)?;
+            } else {
+                return Err(parse_error(position, format!("unexpected '{}'", token)));
+            }
+        }
+
+        if event.weekdays == 0 && !had_date && !had_time {
+            return Err(parse_error(0, "empty calendar event"));
+        }
+
+        Ok(event)
+    }
+}
+
+/// Prints the normalized form
+impl fmt::Display for CalendarEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weekdays != 0 {
+            write_weekdays(f, self.weekdays)?;
+            f.write_str(" ")?;
+        }
+
+        write_component(f, &self.year, 4)?;
+        f.write_str("-")?;
+        write_component(f, &self.month, 2)?;
+        f.write_str(if self.end_of_month { "~" } else { "-" })?;
+        write_component(f, &self.day, 2)?;
+        f.write_str(" ")?;
+        write_component(f, &self.hour, 2)?;
+        f.write_str(":")?;
+        write_component(f, &self.minute, 2)?;
+        f.write_str(":")?;
+        write_component(f, &self.second, 2)
+    }
+}
+
+/// The iterator over the matches of a `CalendarEvent`, see `CalendarEvent::occurrences()`
+pub type CalendarEventIter = ScheduleIter<CalendarEvent>;
+
+impl Schedule for CalendarEvent {
+    fn next_from(&self, moment: &NaiveDateTime) -> Option<NaiveDateTime> {
+        CalendarEvent::next_from(self, moment)
+    }
+}
+
+impl Item {
+    fn value(start: u32) -> Item {
+        Item {
+            start,
+            stop: None,
+            repeat: None,
+        }
+    }
+}
+
+fn expand_shorthand(s: &str) -> Option<&'static str> {
+    match s.to_ascii_lowercase().as_str() {
+        "minutely" => Some("*-*-* *:*:00"),
+        "hourly" => Some("*-*-* *:00:00"),
+        "daily" => Some("*-*-* 00:00:00"),
+        "weekly" => Some("Mon *-*-* 00:00:00"),
+        "monthly" => Some("*-*-01 00:00:00"),
+        "yearly" | "annually" => Some("*-01-01 00:00:00"),
+        "quarterly" => Some("*-01,04,07,10-01 00:00:00"),
+        "semiannually" => Some("*-01,07-01 00:00:00"),
+        _ => None,
+    }
+}
+
+fn is_weekdays(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+fn parse_weekday(position: usize, s: &str) -> Result<u32> {
+    WEEKDAY_NAMES
+        .iter()
+        .position(|(short, long)| short.eq_ignore_ascii_case(s) || long.eq_ignore_ascii_case(s))
+        .map(|i| i as u32)
+        .ok_or_else(|| parse_error(position, format!("unknown weekday '{}'", s)))
+}
+
+fn parse_weekdays(position: usize, token: &str) -> Result<u8> {
+    let mut bits = 0u8;
+    let mut offset = position;
+
+    for item in token.split(',') {
+        let range = item.split_once("..").or_else(|| item.split_once('-'));
+        let (first, last) = match range {
+            Some((a, b)) => (
+                parse_weekday(offset, a)?,
+                parse_weekday(offset + item.len() - b.len(), b)?,
+            ),
+            None => {
+                let day = parse_weekday(offset, item)?;
+                (day, day)
+            },
+        };
+
+        if last < first {
+            return Err(parse_error(offset, format!("invalid range '{}'", item)));
+        }
+
+        for day in first..=last {
+            bits |= 1 << day;
+        }
+        offset += item.len() + 1;
+    }
+
+    Ok(bits)
+}
+
+fn parse_component(position: usize, s: &str, min: u32, max: u32) -> Result<Component> {
+    if s == "*" {
+        return Ok(None);
+    }
+
+    let number = |position: usize, s: &str| -> Result<u32> {
+        match s.parse::<u32>() {
+            Ok(v) if (min..=max).contains(&v) => Ok(v),
+            Ok(v) => Err(parse_error(position, format!("{} is not within {}-{}", v, min, max))),
+            Err(_) => Err(parse_error(position, format!("invalid value '{}'", s))),
+        }
+    };
+
+    let mut items = Vec::new();
+    let mut offset = position;
+
+    for item in s.split(',') {
+        let (range, repeat) = match item.split_once('/') {
+            Some((range, repeat)) => match repeat.parse::<u32>() {
+                Ok(r) if r > 0 => (range, Some(r)),
+                _ => {
+                    let position = offset + range.len() + 1;
+                    return Err(parse_error(position, format!("invalid repetition '{}'", repeat)));
+                },
+            },
+            None => (item, None),
+        };
+
+        let (start, stop) = match range.split_once("..") {
+            _ if range == "*" && repeat.is_some() => (min, None),
+            Some((a, b)) => {
+                let (start, stop) = (number(offset, a)?, number(offset + a.len() + 2, b)?);
+                if stop < start {
+                    return Err(parse_error(offset, format!("invalid range '{}'", range)));
+                }
+                (start, Some(stop))
+            },
+            None => (number(offset, range)?, None),
+        };
+
+        items.push(Item { start, stop, repeat });
+        offset += item.len() + 1;
+    }
+
+    Ok(Some(items))
+}
+
+fn parse_date(event: &mut CalendarEvent, position: usize, token: &str) -> Result<()> {
+    let (rest, day, end_of_month) = match token.rfind(['-', '~']) {
+        Some(i) => (&token[..i], &token[i + 1..], token[i..].starts_with('~')),
+        None => return Err(parse_error(position, "invalid date")),
+    };
+
+    let (year, month) = match rest.split_once('-') {
+        Some((year, month)) => (Some(year), month),
+        None => (None, rest),
+    };
+
+    if let Some(year) = year {
+        event.year = parse_component(position, year, 0, 9999)?;
+    }
+    let month_position = position + year.map_or(0, |y| y.len() + 1);
+    event.month = parse_component(month_position, month, 1, 12)?;
+    event.day = parse_component(position + rest.len() + 1, day, 1, 31)?;
+    event.end_of_month = end_of_month;
+    Ok(())
+}
+
+fn parse_time(event: &mut CalendarEvent, position: usize, token: &str) -> Result<()> {
+    let parts = token.split(':').collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return Err(parse_error(position, "invalid time"));
+    }
+
+    let offset = |i: usize| position + parts[..i].iter().map(|p| p.len() + 1).sum::<usize>();
+    event.hour = parse_component(position, parts[0], 0, 23)?;
+    event.minute = parse_component(offset(1), parts[1], 0, 59)?;
+    event.second = match parts.get(2) {
+        Some(second) => parse_component(offset(2), second, 0, 59)?,
+        None => Some(vec![Item::value(0)]),
+    };
+    Ok(())
+}
+
+// Write runs of three or more days as ranges, like systemd does
+fn write_weekdays(f: &mut fmt::Formatter<'_>, bits: u8) -> fmt::Result {
+    let mut first = true;
+    let mut day = 0;
+
+    while day < 7 {
+        if bits & (1 << day) == 0 {
+            day += 1;
+            continue;
+        }
+
+        let mut last = day;
+        while last < 6 && bits & (1 << (last + 1)) != 0 {
+            last += 1;
+        }
+
+        if !first {
+            f.write_str(",")?;
+        }
+        first = false;
+
+        f.write_str(WEEKDAY_NAMES[day].0)?;
+        match last - day {
+            0 => {},
+            1 => write!(f, ",{}", WEEKDAY_NAMES[last].0)?,
+            _ => write!(f, "..{}", WEEKDAY_NAMES[last].0)?,
+        }
+        day = last + 1;
+    }
+
+    Ok(())
+}
+
+fn write_component(f: &mut fmt::Formatter<'_>, component: &Component, width: usize) -> fmt::Result {
+    let items = match component {
+        None => return f.write_str("*"),
+        Some(items) => items,
+    };
+
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(",")?;
+        }
+        write!(f, "{:0width$}", item.start, width = width)?;
+        if let Some(stop) = item.stop {
+            write!(f, "..{:0width$}", stop, width = width)?;
+        }
+        if let Some(repeat) = item.repeat {
+            write!(f, "/{}", repeat)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn runs(expr: &str, start: NaiveDateTime, n: usize) -> Vec<NaiveDateTime> {
+        expr.parse::<CalendarEvent>()
+            .unwrap()
+            .occurrences(start)
+            .take(n)
+            .map(|tt| *tt.unwrap().get_moment().unwrap())
+            .collect()
+    }
+
+    fn normalized(expr: &str) -> String {
+        expr.parse::<CalendarEvent>().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_normalize() {
+        for (input, expected) in [
+            ("Mon..Fri *-*-* 09:00:00", "Mon..Fri *-*-* 09:00:00"),
+            ("mon,tue,wed 9:0", "Mon..Wed *-*-* 09:00:00"),
+            ("Sat,Sun 10:00", "Sat,Sun *-*-* 10:00:00"),
+            ("Monday-Wednesday,Fri", "Mon..Wed,Fri *-*-* 00:00:00"),
+            ("*-*-01 00:00:00", "*-*-01 00:00:00"),
+            ("*-*-1", "*-*-01 00:00:00"),
+            ("12-25", "*-12-25 00:00:00"),
+            ("2024-2-29 12:30", "2024-02-29 12:30:00"),
+            ("*-02~03", "*-02~03 00:00:00"),
+            ("*:0/15", "*-*-* *:00/15:00"),
+            ("*:*:*", "*-*-* *:*:*"),
+            ("8..17:00,30", "*-*-* 08..17:00,30:00"),
+            ("weekly", "Mon *-*-* 00:00:00"),
+            ("minutely", "*-*-* *:*:00"),
+            ("hourly", "*-*-* *:00:00"),
+            ("daily", "*-*-* 00:00:00"),
+            ("monthly", "*-*-01 00:00:00"),
+            ("annually", "*-01-01 00:00:00"),
+            ("quarterly", "*-01,04,07,10-01 00:00:00"),
+            ("semiannually", "*-01,07-01 00:00:00"),
+        ] {
+            assert_eq!(normalized(input), expected, "{}", input);
+            assert_eq!(normalized(expected), expected, "{}", expected);
+        }
+    }
+
+    #[test]
+    fn test_weekdays_at_nine() {
+        // 2024-01-05 is a Friday
+        let v = runs("Mon..Fri *-*-* 09:00:00", ymd_hms(2024, 1, 5, 10, 0, 0), 2);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 8, 9, 0, 0), ymd_hms(2024, 1, 9, 9, 0, 0)]);
+    }
+
+    #[test]
+    fn test_first_of_month() {
+        let v = runs("*-*-01 00:00:00", ymd_hms(2024, 1, 1, 0, 0, 0), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 0, 0, 0),
+                ymd_hms(2024, 2, 1, 0, 0, 0),
+                ymd_hms(2024, 3, 1, 0, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly() {
+        let v = runs("weekly", ymd_hms(2024, 1, 3, 0, 0, 0), 2);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 8, 0, 0, 0), ymd_hms(2024, 1, 15, 0, 0, 0)]);
+    }
+
+    #[test]
+    fn test_repetition() {
+        let v = runs("*:0/20", ymd_hms(2024, 1, 1, 10, 30, 0), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 10, 40, 0),
+                ymd_hms(2024, 1, 1, 11, 0, 0),
+                ymd_hms(2024, 1, 1, 11, 20, 0)
+            ]
+        );
+
+        let v = runs("*-*-1/10", ymd_hms(2024, 1, 2, 0, 0, 0), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 11, 0, 0, 0),
+                ymd_hms(2024, 1, 21, 0, 0, 0),
+                ymd_hms(2024, 1, 31, 0, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_end_of_month() {
+        let v = runs("*-*~01", ymd_hms(2024, 1, 1, 0, 0, 0), 2);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 31, 0, 0, 0), ymd_hms(2024, 2, 29, 0, 0, 0)]);
+
+        // the last Monday in May (systemd.time(7))
+        let v = runs("Mon *-05~07/1", ymd_hms(2024, 1, 1, 0, 0, 0), 2);
+        assert_eq!(v, vec![ymd_hms(2024, 5, 27, 0, 0, 0), ymd_hms(2025, 5, 26, 0, 0, 0)]);
+    }
+
+    #[test]
+    fn test_fixed_year_ends() {
+        let v = runs("2024-*-01 12:00", ymd_hms(2024, 11, 15, 0, 0, 0), 3);
+        assert_eq!(v, vec![ymd_hms(2024, 12, 1, 12, 0, 0)]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let event = "Sat,Sun *-*-* 10:00".parse::<CalendarEvent>().unwrap();
+        assert!(event.matches(&ymd_hms(2024, 1, 6, 10, 0, 0)));
+        assert!(!event.matches(&ymd_hms(2024, 1, 5, 10, 0, 0)));
+        assert!(!event.matches(&ymd_hms(2024, 1, 6, 10, 0, 1)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let position = |s: &str| s.parse::<CalendarEvent>().unwrap_err().position();
+
+        assert_eq!(position(""), Some(0));
+        assert_eq!(position("Fooday"), Some(0));
+        assert_eq!(position("Mon..Fooday"), Some(5));
+        assert_eq!(position("*-13-01"), Some(2));
+        assert_eq!(position("*-*-32"), Some(4));
+        assert_eq!(position("Mon 25:00"), Some(4));
+        assert_eq!(position("12:61"), Some(3));
+        assert_eq!(position("*:0/0"), Some(4));
+        assert_eq!(position("*-*-* 10:00 UTC"), Some(12));
+        assert_eq!(position("1:2:3:4"), Some(0));
+    }
+}
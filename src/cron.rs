@@ -0,0 +1,569 @@
+//! The module for cron expressions
+//!
+//! Both the standard five fields (minute, hour, day of month, month, day of week) and six fields
+//! with leading seconds are supported. Fields take values, ranges, lists and steps like
+//! `*/15`, `1-5` or `0,30`. Months and days of the week can be given by their English
+//! abbreviations, Sunday is both 0 and 7.
+//!
+//! The common extensions are supported as well:
+//!
+//! * `L` in the day of month is the last day of the month, `L-3` the third to last one
+//! * `15W` in the day of month is the weekday nearest to the 15th, `LW` the last weekday
+//! * `5L` in the day of week is the last Friday of the month
+//! * `5#3` in the day of week is the third Friday of the month
+//! * `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly`
+//!
+//! Like in Vixie cron and cronie, a moment matches if either the day of month or the day of week
+//! matches, if both are restricted and neither starts with `*`. Otherwise both have to match, so
+//! `0 0 */2 * MON` runs on Mondays which are odd days of the month. `?` can be used instead of `*`
+//! for either of them.
+
+use std::str::FromStr;
+
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Timelike;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::indicator::Day;
+use crate::util::days_in_month;
+use crate::util::parse_error;
+use crate::util::split_tokens;
+use crate::util::Schedule;
+use crate::util::ScheduleIter;
+use crate::util::MAX_DAYS_WITHOUT_OCCURRENCE;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayOfMonth {
+    Nth(u32),
+    /// The last day of the month, minus some days
+    Last(u32),
+    NearestWeekday(u32),
+    LastWeekday,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayOfWeek {
+    Every(Day),
+    Nth(Day, u32),
+    Last(Day),
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    seconds: Vec<u32>,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Option<Vec<DayOfMonth>>,
+    months: Vec<u32>,
+    days_of_week: Option<Vec<DayOfWeek>>,
+    // Whether the day of month or the day of week field starts with `*`, like `*/2`
+    day_of_month_starred: bool,
+    day_of_week_starred: bool,
+}
+
+impl Cron {
+    /// Check whether `moment` matches the expression
+    pub fn matches(&self, moment: &NaiveDateTime) -> bool {
+        self.matches_date(moment.date())
+            && self.hours.contains(&moment.hour())
+            && self.minutes.contains(&moment.minute())
+            && self.seconds.contains(&moment.second())
+    }
+
+    /// Find the first match at or after `moment`
+    ///
+    /// Returns `None` if the expression does not match within 400 years, like `0 0 30 2 *`.
+    pub fn next_from(&self, moment: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = moment.date();
+        let mut from = moment.time().with_nanosecond(0);
+        let limit = date
+            .checked_add_signed(Duration::days(MAX_DAYS_WITHOUT_OCCURRENCE))
+            .unwrap_or(NaiveDate::MAX);
+
+        while date <= limit {
+            if !self.months.contains(&date.month()) {
+                // skip to the first day of the next month
+                date = date
+                    .with_day(1)?
+                    .checked_add_signed(Duration::days(days_in_month(date) as i64))?;
+                from = None;
+                continue;
+            }
+
+            if self.matches_date(date) {
+                if let Some(time) = self.first_time_from(from.unwrap_or(NaiveTime::MIN)) {
+                    return Some(date.and_time(time));
+                }
+            }
+
+            date = date.succ_opt()?;
+            from = None;
+        }
+
+        None
+    }
+
+    /// Generate the matches of the expression, starting at `start`
+    pub fn occurrences(&self, start: NaiveDateTime) -> CronIter {
+        ScheduleIter::new(self.clone(), start)
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let dom = self
+            .days_of_month
+            .as_ref()
+            .map(|specs| specs.iter().any(|spec| matches_day_of_month(spec, date)));
+        let dow = self
+            .days_of_week
+            .as_ref()
+            .map(|specs| specs.iter().any(|spec| matches_day_of_week(spec, date)));
+
+        self.months.contains(&date.month())
+            && match (dom, dow) {
+                (Some(dom), Some(dow)) if self.day_of_month_starred || self.day_of_week_starred => dom && dow,
+                (Some(dom), Some(dow)) => dom || dow,
+                (Some(m), None) | (None, Some(m)) => m,
+                (None, None) => true,
+            }
+    }
+
+    // The first time of day at or after `from`
+    fn first_time_from(&self, from: NaiveTime) -> Option<NaiveTime> {
+        for h in self.hours.iter().copied().filter(|h| *h >= from.hour()) {
+            let same_hour = h == from.hour();
+            for m in self
+                .minutes
+                .iter()
+                .copied()
+                .filter(|m| !same_hour || *m >= from.minute())
+            {
+                let same_minute = same_hour && m == from.minute();
+                if let Some(s) = self
+                    .seconds
+                    .iter()
+                    .copied()
+                    .find(|s| !same_minute || *s >= from.second())
+                {
+                    return NaiveTime::from_hms_opt(h, m, s);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl FromStr for Cron {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Cron> {
+        let expanded = match s.trim() {
+            m if m.starts_with('@') => Some(
+                expand_macro(m)
+                    .ok_or_else(|| parse_error(s.len() - s.trim_start().len(), format!("unknown macro '{}'", m)))?,
+            ),
+            _ => None,
+        };
+        let input = expanded.unwrap_or(s);
+
+        let fields = split_tokens(input);
+        let (seconds, rest) = match fields.len() {
+            5 => (None, &fields[..]),
+            6 => (Some(fields[0]), &fields[1..]),
+            n => return Err(parse_error(0, format!("expected 5 or 6 fields, found {}", n))),
+        };
+
+        Ok(Cron {
+            seconds: match seconds {
+                Some(field) => parse_numbers(field, 0, 59, &[])?,
+                None => vec![0],
+            },
+            minutes: parse_numbers(rest[0], 0, 59, &[])?,
+            hours: parse_numbers(rest[1], 0, 23, &[])?,
+            days_of_month: parse_days_of_month(rest[2])?,
+            months: parse_numbers(rest[3], 1, 12, &MONTH_NAMES)?,
+            days_of_week: parse_days_of_week(rest[4])?,
+            day_of_month_starred: rest[2].1.starts_with('*'),
+            day_of_week_starred: rest[4].1.starts_with('*'),
+        })
+    }
+}
+
+/// The iterator over the matches of a `Cron`, see `Cron::occurrences()`
+pub type CronIter = ScheduleIter<Cron>;
+
+impl Schedule for Cron {
+    fn next_from(&self, moment: &NaiveDateTime) -> Option<NaiveDateTime> {
+        Cron::next_from(self, moment)
+    }
+}
+
+// A field of the expression and its byte offset in the input
+type Field<'a> = (usize, &'a str);
+
+fn expand_macro(s: &str) -> Option<&'static str> {
+    match s.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => Some("0 0 1 1 *"),
+        "@monthly" => Some("0 0 1 * *"),
+        "@weekly" => Some("0 0 * * 0"),
+        "@daily" | "@midnight" => Some("0 0 * * *"),
+        "@hourly" => Some("0 * * * *"),
+        _ => None,
+    }
+}
+
+// Split a field into its comma separated items
+fn items((position, field): Field<'_>) -> impl Iterator<Item = Field<'_>> {
+    let mut offset = position;
+    field.split(',').map(move |item| {
+        let result = (offset, item);
+        offset += item.len() + 1;
+        result
+    })
+}
+
+fn parse_value((position, s): Field<'_>, min: u32, max: u32, names: &[&str]) -> Result<u32> {
+    let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+        Some(i) => i as u32 + min,
+        None => s
+            .parse()
+            .map_err(|_| parse_error(position, format!("invalid value '{}'", s)))?,
+    };
+
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(parse_error(
+            position,
+            format!("{} is not within {}-{}", value, min, max),
+        ))
+    }
+}
+
+// Parse a field of values, ranges and steps into its sorted values
+fn parse_numbers(field: Field<'_>, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>> {
+    let mut values = Vec::new();
+
+    for (position, item) in items(field) {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step_position = position + range.len() + 1;
+                match step.parse::<u32>() {
+                    Ok(step) if step > 0 => (range, Some(step)),
+                    _ => return Err(parse_error(step_position, format!("invalid step '{}'", step))),
+                }
+            },
+            None => (item, None),
+        };
+
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((a, b)) => {
+                let first = parse_value((position, a), min, max, names)?;
+                let last = parse_value((position + a.len() + 1, b), min, max, names)?;
+                if last < first {
+                    return Err(parse_error(position, format!("invalid range '{}'", range)));
+                }
+                (first, last)
+            },
+            None => {
+                let value = parse_value((position, range), min, max, names)?;
+                (value, if step.is_some() { max } else { value })
+            },
+        };
+
+        values.extend((first..=last).step_by(step.unwrap_or(1) as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+fn is_unrestricted(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+fn parse_days_of_month(field: Field<'_>) -> Result<Option<Vec<DayOfMonth>>> {
+    if is_unrestricted(field.1) {
+        return Ok(None);
+    }
+
+    let mut specs = Vec::new();
+    for (position, item) in items(field) {
+        let upper = item.to_ascii_uppercase();
+        let spec = if upper == "L" {
+            DayOfMonth::Last(0)
+        } else if upper == "LW" {
+            DayOfMonth::LastWeekday
+        } else if let Some(offset) = upper.strip_prefix("L-") {
+            DayOfMonth::Last(parse_value((position + 2, offset), 0, 30, &[])?)
+        } else if let Some(day) = upper.strip_suffix('W') {
+            DayOfMonth::NearestWeekday(parse_value((position, day), 1, 31, &[])?)
+        } else {
+            specs.extend(
+                parse_numbers((position, item), 1, 31, &[])?
+                    .into_iter()
+                    .map(DayOfMonth::Nth),
+            );
+            continue;
+        };
+        specs.push(spec);
+    }
+
+    Ok(Some(specs))
+}
+
+fn parse_days_of_week(field: Field<'_>) -> Result<Option<Vec<DayOfWeek>>> {
+    if is_unrestricted(field.1) {
+        return Ok(None);
+    }
+
+    let day = |n: u32| Day::all()[(n as usize + 6) % 7];
+    let mut specs = Vec::new();
+
+    for (position, item) in items(field) {
+        let upper = item.to_ascii_uppercase();
+        if let Some((d, nth)) = upper.split_once('#') {
+            let d = parse_value((position, d), 0, 7, &DAY_NAMES)?;
+            let nth = parse_value((position + item.find('#').unwrap_or(0) + 1, nth), 1, 5, &[])?;
+            specs.push(DayOfWeek::Nth(day(d), nth));
+        } else if let Some(d) = upper.strip_suffix('L').filter(|d| !d.is_empty()) {
+            specs.push(DayOfWeek::Last(day(parse_value((position, d), 0, 7, &DAY_NAMES)?)));
+        } else {
+            specs.extend(
+                parse_numbers((position, item), 0, 7, &DAY_NAMES)?
+                    .into_iter()
+                    .map(|d| DayOfWeek::Every(day(d))),
+            );
+        }
+    }
+
+    Ok(Some(specs))
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    date.weekday().num_days_from_monday() < 5
+}
+
+fn matches_day_of_month(spec: &DayOfMonth, date: NaiveDate) -> bool {
+    let last = days_in_month(date);
+
+    match *spec {
+        DayOfMonth::Nth(n) => date.day() == n,
+        DayOfMonth::Last(offset) => offset < last && date.day() == last - offset,
+        DayOfMonth::NearestWeekday(n) => {
+            if n > last || !is_weekday(date) {
+                return false;
+            }
+
+            // the weekday nearest to the n-th, without leaving the month
+            let target = date.with_day(n).expect("day exists in month");
+            let nearest = match target.weekday() {
+                chrono::Weekday::Sat if n == 1 => n + 2,
+                chrono::Weekday::Sat => n - 1,
+                chrono::Weekday::Sun if n == last => n - 2,
+                chrono::Weekday::Sun => n + 1,
+                _ => n,
+            };
+            date.day() == nearest
+        },
+        DayOfMonth::LastWeekday => {
+            is_weekday(date) && (date.day() + 1..=last).all(|d| !is_weekday(date.with_day(d).expect("day exists")))
+        },
+    }
+}
+
+fn matches_day_of_week(spec: &DayOfWeek, date: NaiveDate) -> bool {
+    let (day, matches) = match *spec {
+        DayOfWeek::Every(day) => (day, true),
+        DayOfWeek::Nth(day, nth) => (day, date.day0() / 7 + 1 == nth),
+        DayOfWeek::Last(day) => (day, date.day() + 7 > days_in_month(date)),
+    };
+
+    matches && Day::from(date.weekday()) == day
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn runs(expr: &str, start: NaiveDateTime, n: usize) -> Vec<NaiveDateTime> {
+        expr.parse::<Cron>()
+            .unwrap()
+            .occurrences(start)
+            .take(n)
+            .map(|tt| *tt.unwrap().get_moment().unwrap())
+            .collect()
+    }
+
+    fn dates(v: Vec<NaiveDateTime>) -> Vec<(i32, u32, u32)> {
+        v.into_iter().map(|m| (m.year(), m.month(), m.day())).collect()
+    }
+
+    #[test]
+    fn test_next_runs() {
+        // 2024-01-05 is a Friday
+        let v = runs("*/20 9-17 * * MON-FRI", ymd_hms(2024, 1, 5, 17, 30, 0), 4);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 5, 17, 40, 0),
+                ymd_hms(2024, 1, 8, 9, 0, 0),
+                ymd_hms(2024, 1, 8, 9, 20, 0),
+                ymd_hms(2024, 1, 8, 9, 40, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_start_is_included() {
+        let v = runs("30 12 * * *", ymd_hms(2024, 1, 1, 12, 30, 0), 2);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 1, 12, 30, 0), ymd_hms(2024, 1, 2, 12, 30, 0)]);
+
+        let v = runs("30 12 * * *", ymd_hms(2024, 1, 1, 12, 30, 1), 1);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 2, 12, 30, 0)]);
+    }
+
+    #[test]
+    fn test_seconds_field() {
+        let v = runs("*/15 0 12 * * *", ymd_hms(2024, 1, 1, 12, 0, 20), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 12, 0, 30),
+                ymd_hms(2024, 1, 1, 12, 0, 45),
+                ymd_hms(2024, 1, 2, 12, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_macros() {
+        let start = ymd_hms(2024, 3, 15, 10, 0, 0);
+        assert_eq!(runs("@daily", start, 1), vec![ymd_hms(2024, 3, 16, 0, 0, 0)]);
+        assert_eq!(runs("@hourly", start, 1), vec![ymd_hms(2024, 3, 15, 10, 0, 0)]);
+        assert_eq!(runs("@weekly", start, 1), vec![ymd_hms(2024, 3, 17, 0, 0, 0)]);
+        assert_eq!(runs("@MONTHLY", start, 1), vec![ymd_hms(2024, 4, 1, 0, 0, 0)]);
+        assert_eq!(runs("@annually", start, 1), vec![ymd_hms(2025, 1, 1, 0, 0, 0)]);
+    }
+
+    #[test]
+    fn test_last_day_of_month() {
+        let start = ymd_hms(2024, 1, 1, 0, 0, 0);
+        assert_eq!(
+            dates(runs("0 0 L * *", start, 3)),
+            vec![(2024, 1, 31), (2024, 2, 29), (2024, 3, 31)]
+        );
+        assert_eq!(dates(runs("0 0 L-1 * *", start, 2)), vec![(2024, 1, 30), (2024, 2, 28)]);
+        // 2024-03-31 is a Sunday, 2024-06-30 as well
+        assert_eq!(
+            dates(runs("0 0 LW 3,6 *", start, 2)),
+            vec![(2024, 3, 29), (2024, 6, 28)]
+        );
+    }
+
+    #[test]
+    fn test_nearest_weekday() {
+        let start = ymd_hms(2024, 1, 1, 0, 0, 0);
+        // 2024-06-15 is a Saturday, 2024-09-15 a Sunday, 2024-06-01 a Saturday
+        assert_eq!(dates(runs("0 0 15W 6 *", start, 1)), vec![(2024, 6, 14)]);
+        assert_eq!(dates(runs("0 0 15W 9 *", start, 1)), vec![(2024, 9, 16)]);
+        assert_eq!(dates(runs("0 0 1W 6 *", start, 1)), vec![(2024, 6, 3)]);
+        assert_eq!(dates(runs("0 0 10W 1 *", start, 1)), vec![(2024, 1, 10)]);
+    }
+
+    #[test]
+    fn test_nth_and_last_weekday() {
+        let start = ymd_hms(2024, 1, 1, 0, 0, 0);
+        assert_eq!(dates(runs("0 0 * * 5#3", start, 2)), vec![(2024, 1, 19), (2024, 2, 16)]);
+        assert_eq!(dates(runs("0 0 ? * FRI#1", start, 1)), vec![(2024, 1, 5)]);
+        assert_eq!(dates(runs("0 0 * * 5L", start, 2)), vec![(2024, 1, 26), (2024, 2, 23)]);
+        assert_eq!(dates(runs("0 0 * * 0L", start, 1)), vec![(2024, 1, 28)]);
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // the 13th and every Friday
+        let v = runs("0 0 13 * FRI", ymd_hms(2024, 1, 1, 0, 0, 0), 4);
+        assert_eq!(
+            dates(v),
+            vec![(2024, 1, 5), (2024, 1, 12), (2024, 1, 13), (2024, 1, 19)]
+        );
+    }
+
+    #[test]
+    fn test_starred_day_field_needs_both_to_match() {
+        // Mondays which are odd days of the month, January 1st 2024 is a Monday
+        let v = runs("0 0 */2 * MON", ymd_hms(2024, 1, 1, 0, 0, 0), 4);
+        assert_eq!(dates(v), vec![(2024, 1, 1), (2024, 1, 15), (2024, 1, 29), (2024, 2, 5)]);
+
+        // the 13th on Sundays and Fridays
+        let v = runs("0 0 13 * */5", ymd_hms(2024, 1, 1, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2024, 9, 13), (2024, 10, 13)]);
+    }
+
+    #[test]
+    fn test_sunday_is_0_and_7() {
+        let a = "0 0 * * 0".parse::<Cron>().unwrap();
+        let b = "0 0 * * 7".parse::<Cron>().unwrap();
+        let c = "0 0 * * sun".parse::<Cron>().unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+    }
+
+    #[test]
+    fn test_lists_and_steps() {
+        let cron = "5,10-12,40-59/10 * * * *".parse::<Cron>().unwrap();
+        assert_eq!(cron.minutes, vec![5, 10, 11, 12, 40, 50]);
+
+        let cron = "30/15 * * * *".parse::<Cron>().unwrap();
+        assert_eq!(cron.minutes, vec![30, 45]);
+
+        let cron = "0 0 * JAN-MAR,dec *".parse::<Cron>().unwrap();
+        assert_eq!(cron.months, vec![1, 2, 3, 12]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let cron = "0 9 * * MON-FRI".parse::<Cron>().unwrap();
+        assert!(cron.matches(&ymd_hms(2024, 1, 5, 9, 0, 0)));
+        assert!(!cron.matches(&ymd_hms(2024, 1, 6, 9, 0, 0)));
+        assert!(!cron.matches(&ymd_hms(2024, 1, 5, 9, 1, 0)));
+    }
+
+    #[test]
+    fn test_never_matching() {
+        assert!(runs("0 0 30 2 *", ymd_hms(2024, 1, 1, 0, 0, 0), 1).is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let position = |s: &str| s.parse::<Cron>().unwrap_err().position();
+
+        assert_eq!(position("* * * *"), Some(0));
+        assert_eq!(position("60 * * * *"), Some(0));
+        assert_eq!(position("0 24 * * *"), Some(2));
+        assert_eq!(position("0 0 1,32 * *"), Some(6));
+        assert_eq!(position("0 0 * FOO *"), Some(6));
+        assert_eq!(position("0 0 * * 1-8"), Some(10));
+        assert_eq!(position("0 0 * * 5#6"), Some(10));
+        assert_eq!(position("*/0 * * * *"), Some(2));
+        assert_eq!(position("0 5-1 * * *"), Some(2));
+        assert_eq!(position("@reboot"), Some(0));
+        assert_eq!(position("0 0 ? * ?x"), Some(8));
+    }
+}
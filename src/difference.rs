@@ -0,0 +1,310 @@
+//! The module for calendar-aware differences between two moments
+//!
+//! Other than `TimeType::get_days()` and friends, which assume 30-day months, the difference
+//! calculated here respects the real lengths of months and leap years. It breaks the distance
+//! between two moments down into the units the caller asks for, like "3 years, 2 months and 11
+//! days".
+
+use std::fmt;
+
+use chrono::Datelike;
+use chrono::Months;
+use chrono::NaiveDateTime;
+use chrono::Timelike;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// The units a `Difference` can be broken down into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Years,
+    Months,
+    Weeks,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl Unit {
+    /// Years, months and days, as used for ages and tenures
+    pub const YMD: &'static [Unit] = &[Unit::Years, Unit::Months, Unit::Days];
+
+    /// All units, from years to seconds
+    pub const ALL: &'static [Unit] = &[
+        Unit::Years,
+        Unit::Months,
+        Unit::Weeks,
+        Unit::Days,
+        Unit::Hours,
+        Unit::Minutes,
+        Unit::Seconds,
+    ];
+}
+
+/// A mixed-unit difference between two moments
+///
+/// All fields are non-negative, the direction of the difference is stored separately. Units which
+/// were not requested are zero, their amount is carried over into the next smaller requested
+/// unit. Whatever is left over after the smallest requested unit is truncated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Difference {
+    negative: bool,
+    years: i64,
+    months: i64,
+    weeks: i64,
+    days: i64,
+    hours: i64,
+    minutes: i64,
+    seconds: i64,
+}
+
+impl Difference {
+    /// Calculate the difference from `from` to `to`, broken down into `units`
+    ///
+    /// Years and months are counted as whole calendar years and months. If adding months to
+    /// `from` lands on a day which does not exist in the target month (for example the 31st),
+    /// the last day of that month is used instead, so from January 31st to February 29th 2024 is
+    /// one month.
+    ///
+    /// If `to` lies before `from`, the difference is negative and is calculated from `to` to
+    /// `from`.
+    pub fn between(from: &NaiveDateTime, to: &NaiveDateTime, units: &[Unit]) -> Result<Difference> {
+        let negative = to < from;
+        let (from, to) = if negative { (*to, *from) } else { (*from, *to) };
+
+        let mut diff = Difference {
+            negative,
+            ..Difference::default()
+        };
+
+        let wants = |u: Unit| units.contains(&u);
+
+        let mut counted_months = 0;
+        if wants(Unit::Years) || wants(Unit::Months) {
+            let total_months = whole_months_between(&from, &to)?;
+
+            if wants(Unit::Years) {
+                diff.years = total_months / 12;
+                counted_months = diff.years * 12;
+            }
+
+            // if months are not requested, the remainder is counted in smaller units
+            if wants(Unit::Months) {
+                diff.months = total_months - counted_months;
+                counted_months = total_months;
+            }
+        }
+
+        let mut rest = (to - add_months(&from, counted_months)?).num_seconds();
+
+        for (unit, seconds, field) in [
+            (Unit::Weeks, 7 * 24 * 60 * 60, &mut diff.weeks),
+            (Unit::Days, 24 * 60 * 60, &mut diff.days),
+            (Unit::Hours, 60 * 60, &mut diff.hours),
+            (Unit::Minutes, 60, &mut diff.minutes),
+            (Unit::Seconds, 1, &mut diff.seconds),
+        ] {
+            if wants(unit) {
+                *field = rest / seconds;
+                rest %= seconds;
+            }
+        }
+
+        Ok(diff)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn years(&self) -> i64 {
+        self.years
+    }
+
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    pub fn weeks(&self) -> i64 {
+        self.weeks
+    }
+
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    pub fn hours(&self) -> i64 {
+        self.hours
+    }
+
+    pub fn minutes(&self) -> i64 {
+        self.minutes
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+}
+
+/// Formats the difference like "3 years, 2 months and 11 days", leaving out zero units
+///
+/// A negative difference of several units is wrapped as a whole: "-(3 years and 11 days)".
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            (self.years, "year"),
+            (self.months, "month"),
+            (self.weeks, "week"),
+            (self.days, "day"),
+            (self.hours, "hour"),
+            (self.minutes, "minute"),
+            (self.seconds, "second"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n != 0)
+        .map(|(n, name)| format!("{} {}{}", n, name, if *n == 1 { "" } else { "s" }))
+        .collect::<Vec<_>>();
+
+        let sign = if self.negative { "-" } else { "" };
+        match parts.split_last() {
+            None => write!(f, "0 seconds"),
+            Some((last, [])) => write!(f, "{}{}", sign, last),
+            Some((last, init)) if self.negative => write!(f, "-({} and {})", init.join(", "), last),
+            Some((last, init)) => write!(f, "{} and {}", init.join(", "), last),
+        }
+    }
+}
+
+fn add_months(ndt: &NaiveDateTime, months: i64) -> Result<NaiveDateTime> {
+    u32::try_from(months)
+        .ok()
+        .and_then(|m| ndt.checked_add_months(Months::new(m)))
+        .ok_or(Error::OutOfRange(
+            ndt.year().into(),
+            ndt.month(),
+            ndt.day(),
+            ndt.hour(),
+            ndt.minute(),
+            ndt.second(),
+        ))
+}
+
+// The number of whole calendar months from `from` to `to`, with `from <= to`
+fn whole_months_between(from: &NaiveDateTime, to: &NaiveDateTime) -> Result<i64> {
+    let mut months = (to.year() as i64 - from.year() as i64) * 12 + (to.month() as i64 - from.month() as i64);
+
+    while months > 0 && add_months(from, months)? > *to {
+        months -= 1;
+    }
+
+    Ok(months)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timetype::TimeType as TT;
+    use crate::util::tests::ymd;
+    use crate::util::tests::ymd_hms;
+
+    #[test]
+    fn test_ymd() {
+        let d = Difference::between(&ymd(2021, 1, 5), &ymd(2024, 3, 16), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (3, 2, 11));
+        assert!(!d.is_negative());
+        assert_eq!(d.to_string(), "3 years, 2 months and 11 days");
+    }
+
+    #[test]
+    fn test_month_lengths() {
+        // February 2024 has 29 days
+        let d = Difference::between(&ymd(2024, 2, 10), &ymd(2024, 3, 9), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (0, 0, 28));
+
+        let d = Difference::between(&ymd(2023, 2, 10), &ymd(2023, 3, 9), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (0, 0, 27));
+
+        let d = Difference::between(&ymd(2024, 1, 31), &ymd(2024, 2, 29), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (0, 1, 0));
+
+        let d = Difference::between(&ymd(2024, 1, 31), &ymd(2024, 3, 1), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (0, 1, 1));
+    }
+
+    #[test]
+    fn test_leap_day_birthday() {
+        let d = Difference::between(&ymd(2020, 2, 29), &ymd(2021, 2, 28), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (1, 0, 0));
+
+        let d = Difference::between(&ymd(2020, 2, 29), &ymd(2024, 2, 28), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (3, 11, 30));
+    }
+
+    #[test]
+    fn test_negative() {
+        let d = Difference::between(&ymd(2024, 3, 16), &ymd(2021, 1, 5), Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (3, 2, 11));
+        assert!(d.is_negative());
+        assert_eq!(d.to_string(), "-(3 years, 2 months and 11 days)");
+    }
+
+    #[test]
+    fn test_negative_single_unit() {
+        let d = Difference::between(&ymd(2024, 3, 16), &ymd(2024, 3, 5), Unit::YMD).unwrap();
+        assert!(d.is_negative());
+        assert_eq!(d.to_string(), "-11 days");
+    }
+
+    #[test]
+    fn test_unit_selection() {
+        let from = ymd_hms(2023, 11, 20, 8, 0, 0);
+        let to = ymd_hms(2024, 1, 3, 17, 30, 15);
+
+        let d = Difference::between(&from, &to, &[Unit::Months, Unit::Days]).unwrap();
+        assert_eq!((d.years(), d.months(), d.days(), d.hours()), (0, 1, 14, 0));
+
+        let d = Difference::between(&from, &to, &[Unit::Days, Unit::Hours]).unwrap();
+        assert_eq!((d.days(), d.hours(), d.minutes()), (44, 9, 0));
+
+        let d = Difference::between(&from, &to, &[Unit::Weeks, Unit::Days]).unwrap();
+        assert_eq!((d.weeks(), d.days()), (6, 2));
+
+        let d = Difference::between(&from, &to, &[Unit::Years, Unit::Days]).unwrap();
+        assert_eq!((d.years(), d.days()), (0, 44));
+
+        let d = Difference::between(&from, &to, Unit::ALL).unwrap();
+        assert_eq!(
+            (
+                d.years(),
+                d.months(),
+                d.weeks(),
+                d.days(),
+                d.hours(),
+                d.minutes(),
+                d.seconds()
+            ),
+            (0, 1, 2, 0, 9, 30, 15)
+        );
+        assert_eq!(d.to_string(), "1 month, 2 weeks, 9 hours, 30 minutes and 15 seconds");
+    }
+
+    #[test]
+    fn test_zero() {
+        let d = Difference::between(&ymd(2024, 1, 1), &ymd(2024, 1, 1), Unit::YMD).unwrap();
+        assert_eq!(d, Difference::default());
+        assert_eq!(d.to_string(), "0 seconds");
+    }
+
+    #[test]
+    fn test_timetype_interface() {
+        let from = TT::moment(ymd(2021, 1, 5));
+        let to = TT::moment(ymd(2024, 1, 5)) + TT::days(11) + TT::months(2);
+
+        let d = from.difference(&to, Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (3, 2, 11));
+
+        assert!(from.difference(&TT::days(1), Unit::YMD).is_err());
+    }
+}
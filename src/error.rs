@@ -1,47 +1,215 @@
+use std::fmt;
+
+use chrono::NaiveDateTime;
 use thiserror::Error;
 
 use crate::timetype::TimeType;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// The category an `Error` belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The input could not be parsed
+    Parse,
+
+    /// An expression could not be calculated
+    Evaluation,
+
+    /// A function was called with an argument it cannot handle
+    Argument,
+
+    /// An amount became too large to be represented
+    Overflow,
+
+    /// A date, time or timestamp does not exist or is not representable
+    OutOfRange,
+}
+
+impl ErrorKind {
+    /// A stable, machine-readable code for this category
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Parse => "parse",
+            ErrorKind::Evaluation => "evaluation",
+            ErrorKind::Argument => "argument",
+            ErrorKind::Overflow => "overflow",
+            ErrorKind::OutOfRange => "out-of-range",
+        }
+    }
+}
+
+/// The operation which was attempted when an `Error` occurred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Add,
+    Subtract,
+    EndOfYear,
+    EndOfMonth,
+    EndOfDay,
+    EndOfHour,
+    EndOfMinute,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::EndOfYear => "end-of-year",
+            Operation::EndOfMonth => "end-of-month",
+            Operation::EndOfDay => "end-of-day",
+            Operation::EndOfHour => "end-of-hour",
+            Operation::EndOfMinute => "end-of-minute",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Error)]
 pub enum Error {
-    #[error("Unknown Error")]
-    UnknownError,
+    // Parse errors
+    #[error("Parse error at position {position}: {message}")]
+    Parse { position: usize, message: String },
 
+    // Evaluation errors
     #[error("Cannot add: {0:?} + {1:?}")]
     CannotAdd(TimeType, TimeType),
 
     #[error("Cannot subtract: {0:?} - {1:?}")]
     CannotSub(TimeType, TimeType),
 
+    #[error("Cannot calculate {0} on {1:?}")]
+    CannotCalculate(Operation, TimeType),
+
+    // Argument errors
     #[error("The passed argument is not an amount: {0:?}")]
-    ArgumentErrorNotAnAmount(TimeType),
+    NotAnAmount(TimeType),
+
+    #[error("The passed argument is not a moment: {0:?}")]
+    NotAMoment(TimeType),
 
-    #[error("The passed argument is not a moment, but a {0}")]
-    ArgumentErrorNotAMoment(&'static str),
+    #[error("Invalid month: {0}")]
+    InvalidMonth(u32),
 
-    #[error("Argument Error: Cannot calculate end-of-year on a {0:?}")]
-    CannotCalculateEndOfYearOn(TimeType),
+    #[error("Invalid recurrence rule: {0}")]
+    InvalidRecurrence(String),
 
-    #[error("Argument Error: Cannot calculate end-of-month on a {0:?}")]
-    CannotCalculateEndOfMonthOn(TimeType),
+    #[error("Invalid interval: {1} is before {0}")]
+    InvalidInterval(NaiveDateTime, NaiveDateTime),
 
-    #[error("Cannot compare Day to non-Moment TimeType: {0:?}")]
-    CannotCompareDayTo(&'static str),
+    // Overflow errors
+    #[error("Overflow: {1:?} {0} {2:?}")]
+    Overflow(Operation, TimeType, TimeType),
+
+    // Out-of-range errors
+    #[error("Out of range: {0}-{1}-{2}T{3}:{4}:{5}")]
+    OutOfRange(i64, u32, u32, u32, u32, u32),
+
+    #[error("Timestamp out of range: {0}")]
+    TimestampOutOfRange(i64),
+}
 
-    #[error("Cannot compare Month to non-Moment TimeType: {0:?}")]
-    CannotCompareMonthTo(&'static str),
+impl Error {
+    /// The category of this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Parse { .. } => ErrorKind::Parse,
+            Error::CannotAdd(..) | Error::CannotSub(..) | Error::CannotCalculate(..) => ErrorKind::Evaluation,
+            Error::NotAnAmount(_)
+            | Error::NotAMoment(_)
+            | Error::InvalidMonth(_)
+            | Error::InvalidRecurrence(_)
+            | Error::InvalidInterval(..) => ErrorKind::Argument,
+            Error::Overflow(..) => ErrorKind::Overflow,
+            Error::OutOfRange(..) | Error::TimestampOutOfRange(_) => ErrorKind::OutOfRange,
+        }
+    }
+
+    /// A stable, machine-readable code for this error
+    ///
+    /// Codes are prefixed with the code of the `ErrorKind`, so "evaluation/cannot-add" can be
+    /// matched on as a whole or by its category.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Parse { .. } => "parse/syntax",
+            Error::CannotAdd(..) => "evaluation/cannot-add",
+            Error::CannotSub(..) => "evaluation/cannot-subtract",
+            Error::CannotCalculate(..) => "evaluation/cannot-calculate",
+            Error::NotAnAmount(_) => "argument/not-an-amount",
+            Error::NotAMoment(_) => "argument/not-a-moment",
+            Error::InvalidMonth(_) => "argument/invalid-month",
+            Error::InvalidRecurrence(_) => "argument/invalid-recurrence",
+            Error::InvalidInterval(..) => "argument/invalid-interval",
+            Error::Overflow(..) => "overflow/amount",
+            Error::OutOfRange(..) => "out-of-range/date",
+            Error::TimestampOutOfRange(_) => "out-of-range/timestamp",
+        }
+    }
+
+    /// The operation which was attempted, if the error occurred while calculating
+    pub fn operation(&self) -> Option<Operation> {
+        match self {
+            Error::CannotAdd(..) => Some(Operation::Add),
+            Error::CannotSub(..) => Some(Operation::Subtract),
+            Error::CannotCalculate(op, _) | Error::Overflow(op, ..) => Some(*op),
+            _ => None,
+        }
+    }
+
+    /// The offending subexpression, if there is one
+    pub fn subexpression(&self) -> Option<TimeType> {
+        match self {
+            Error::CannotAdd(a, b) | Error::Overflow(Operation::Add, a, b) => Some(a.clone() + b.clone()),
+            Error::CannotSub(a, b) | Error::Overflow(Operation::Subtract, a, b) => Some(a.clone() - b.clone()),
+            Error::CannotCalculate(_, tt) | Error::NotAnAmount(tt) | Error::NotAMoment(tt) => Some(tt.clone()),
+            _ => None,
+        }
+    }
+
+    /// The byte offset in the input at which parsing failed, for parse errors
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Error::Parse { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+}
 
-    #[error("Out of bounds: {0}-{1}-{2}T{3}:{4}:{5}")]
-    OutOfBounds(i32, u32, u32, u32, u32, u32),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timetype::TimeType as TT;
 
-    #[error("Cannot calculate date for iterator")]
-    NotADateInsideIterator,
+    #[test]
+    fn test_codes_are_prefixed_with_kind() {
+        let errors = vec![
+            Error::Parse {
+                position: 0,
+                message: String::from("x"),
+            },
+            Error::CannotAdd(TT::days(1), TT::days(1)),
+            Error::CannotSub(TT::days(1), TT::days(1)),
+            Error::CannotCalculate(Operation::EndOfYear, TT::days(1)),
+            Error::NotAnAmount(TT::days(1)),
+            Error::NotAMoment(TT::days(1)),
+            Error::InvalidMonth(13),
+            Error::InvalidRecurrence(String::from("x")),
+            Error::InvalidInterval(NaiveDateTime::MIN, NaiveDateTime::MAX),
+            Error::Overflow(Operation::Add, TT::days(1), TT::days(1)),
+            Error::OutOfRange(2024, 2, 30, 0, 0, 0),
+            Error::TimestampOutOfRange(0),
+        ];
 
-    #[error("Unknown parser error")]
-    UnknownParserError,
+        for e in errors {
+            assert!(e.code().starts_with(e.kind().code()), "{}", e.code());
+        }
+    }
 
-    #[error("Tokenizer error")]
-    NomError(String),
+    #[test]
+    fn test_subexpression() {
+        let e = Error::CannotAdd(TT::days(1), TT::years(1).end_of_year());
+        assert_eq!(e.operation(), Some(Operation::Add));
+        assert_eq!(e.subexpression(), Some(TT::days(1) + TT::years(1).end_of_year()));
+    }
 }
@@ -0,0 +1,248 @@
+//! The module for explaining calculations
+//!
+//! `TimeType::explain()` calculates a TimeType just like `TimeType::calculate()` does, but also
+//! records every reduction step which was applied on the way to the result. This can be used to
+//! show why an expression like "2024-01-31 + 1 month - 1 day" results in the moment it results
+//! in.
+
+use std::fmt;
+
+use crate::error::Result;
+use crate::timetype::TimeType;
+
+/// The rule which was applied in a single reduction step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    AddToSeconds,
+    AddToMinutes,
+    AddToHours,
+    AddToDays,
+    AddToMonths,
+    AddToYears,
+    AddToMoment,
+    AddToDate,
+    AddToTime,
+
+    SubFromSeconds,
+    SubFromMinutes,
+    SubFromHours,
+    SubFromDays,
+    SubFromMonths,
+    SubFromYears,
+    SubFromMoment,
+    SubFromDate,
+    SubFromTime,
+
+    EndOfYear,
+    EndOfMonth,
+    EndOfDay,
+    EndOfHour,
+    EndOfMinute,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rule::AddToSeconds => "add to seconds",
+            Rule::AddToMinutes => "add to minutes",
+            Rule::AddToHours => "add to hours",
+            Rule::AddToDays => "add to days",
+            Rule::AddToMonths => "add to months",
+            Rule::AddToYears => "add to years",
+            Rule::AddToMoment => "add to moment",
+            Rule::AddToDate => "add to date",
+            Rule::AddToTime => "add to time",
+            Rule::SubFromSeconds => "subtract from seconds",
+            Rule::SubFromMinutes => "subtract from minutes",
+            Rule::SubFromHours => "subtract from hours",
+            Rule::SubFromDays => "subtract from days",
+            Rule::SubFromMonths => "subtract from months",
+            Rule::SubFromYears => "subtract from years",
+            Rule::SubFromMoment => "subtract from moment",
+            Rule::SubFromDate => "subtract from date",
+            Rule::SubFromTime => "subtract from time",
+            Rule::EndOfYear => "end of year",
+            Rule::EndOfMonth => "end of month",
+            Rule::EndOfDay => "end of day",
+            Rule::EndOfHour => "end of hour",
+            Rule::EndOfMinute => "end of minute",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A single reduction step: the `input` node was reduced to `output` by applying `rule`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    input: TimeType,
+    rule: Rule,
+    output: TimeType,
+}
+
+impl Step {
+    pub fn input(&self) -> &TimeType {
+        &self.input
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn output(&self) -> &TimeType {
+        &self.output
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {} ({})", self.input, self.output, self.rule)
+    }
+}
+
+/// The result of a calculation plus the ordered reduction steps which lead to it
+///
+/// Steps are ordered by completion, so inner nodes come before the nodes which contain them and
+/// the last step is the outermost reduction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    result: TimeType,
+    steps: Vec<Step>,
+}
+
+impl Explanation {
+    pub fn result(&self) -> &TimeType {
+        &self.result
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn into_result(self) -> TimeType {
+        self.result
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{}. {}", i + 1, step)?;
+        }
+        write!(f, "= {}", self.result)
+    }
+}
+
+/// Collector for reduction steps, threaded through the calculation
+///
+/// A disabled trace does not clone anything, so calculating without explaining does not pay for
+/// the bookkeeping.
+pub(crate) struct Trace(Option<Vec<Step>>);
+
+impl Trace {
+    pub(crate) fn disabled() -> Trace {
+        Trace(None)
+    }
+
+    pub(crate) fn enabled() -> Trace {
+        Trace(Some(Vec::new()))
+    }
+
+    /// Build the input node of a step, but only if the trace is enabled
+    pub(crate) fn input<F>(&self, f: F) -> Option<TimeType>
+    where
+        F: FnOnce() -> TimeType,
+    {
+        self.0.as_ref().map(|_| f())
+    }
+
+    /// Record a step if the trace is enabled and the step was successful, pass through `result`
+    pub(crate) fn record(&mut self, rule: Rule, input: Option<TimeType>, result: Result<TimeType>) -> Result<TimeType> {
+        if let (Some(steps), Some(input), Ok(output)) = (self.0.as_mut(), input, result.as_ref()) {
+            steps.push(Step {
+                input,
+                rule,
+                output: output.clone(),
+            });
+        }
+        result
+    }
+
+    pub(crate) fn into_explanation(self, result: TimeType) -> Explanation {
+        Explanation {
+            result,
+            steps: self.0.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timetype::TimeType as TT;
+    use crate::util::tests::moment;
+
+    #[test]
+    fn test_explain_result_equals_calculate() {
+        let tt = moment(2024, 1, 31) + (TT::months(1) - TT::days(1));
+        let explanation = tt.clone().explain().unwrap();
+        assert_eq!(explanation.result(), &tt.calculate().unwrap());
+    }
+
+    #[test]
+    fn test_explain_month_minus_day() {
+        // the amounts are combined first, 1 month minus 1 day is 29 days
+        let explanation = (moment(2024, 1, 31) + (TT::months(1) - TT::days(1))).explain().unwrap();
+
+        assert_eq!(explanation.result(), &moment(2024, 2, 29));
+
+        let steps = explanation.steps();
+        assert_eq!(steps.len(), 3);
+
+        assert_eq!(steps[0].input(), &(TT::months(1) - TT::days(1)));
+        assert_eq!(steps[0].rule(), Rule::SubFromMonths);
+        assert_eq!(steps[0].output(), &TT::days(29));
+
+        assert_eq!(steps[1].input(), &(moment(2024, 1, 31) + TT::days(29)));
+        assert_eq!(steps[1].rule(), Rule::AddToMoment);
+        assert_eq!(steps[1].output(), &moment(2024, 2, 29));
+
+        assert_eq!(steps[2].input(), &(moment(2024, 1, 31) + (TT::months(1) - TT::days(1))));
+        assert_eq!(steps[2].rule(), Rule::AddToMoment);
+        assert_eq!(steps[2].output(), &moment(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_explain_end_of_month() {
+        let explanation = (moment(2024, 1, 15) + TT::months(1)).end_of_month().explain().unwrap();
+
+        assert_eq!(explanation.result(), &moment(2024, 2, 29));
+
+        let rules = explanation.steps().iter().map(Step::rule).collect::<Vec<_>>();
+        assert_eq!(rules, vec![Rule::AddToMoment, Rule::EndOfMonth]);
+    }
+
+    #[test]
+    fn test_explain_plain_value() {
+        let explanation = TT::days(1).explain().unwrap();
+        assert_eq!(explanation.result(), &TT::days(1));
+        assert!(explanation.steps().is_empty());
+    }
+
+    #[test]
+    fn test_explain_error() {
+        assert!((TT::days(1) + moment(2024, 1, 1)).explain().is_err());
+    }
+
+    #[test]
+    fn test_explain_display() {
+        let explanation = (moment(2024, 1, 31) + (TT::months(1) - TT::days(1))).explain().unwrap();
+
+        assert_eq!(
+            explanation.to_string(),
+            "1. 1 month - 1 day => 29 days (subtract from months)\n\
+             2. 2024-01-31 00:00:00 + 29 days => 2024-02-29 00:00:00 (add to moment)\n\
+             3. 2024-01-31 00:00:00 + (1 month - 1 day) => 2024-02-29 00:00:00 (add to moment)\n\
+             = 2024-02-29 00:00:00"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! The module for fiscal calendars
+//!
+//! A fiscal calendar describes a business year which does not necessarily start on January 1st.
+//! It either follows calendar months (for example April 1st to March 31st), or it consists of
+//! whole weeks, grouped into quarters of 13 weeks following a 4-4-5, 4-5-4 or 5-4-4 pattern.
+//!
+//! Fiscal years are named after the calendar year they nominally end in, so with a fiscal year starting on
+//! April 1st, the fiscal year 2025 lasts from 2024-04-01 to 2025-03-31.
+
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::indicator::Day;
+use crate::indicator::Month;
+
+/// The number of weeks in each of the three periods of a fiscal quarter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeekPattern {
+    FourFourFive,
+    FourFiveFour,
+    FiveFourFour,
+}
+
+impl WeekPattern {
+    fn weeks_per_period(&self) -> [u32; 3] {
+        match self {
+            WeekPattern::FourFourFive => [4, 4, 5],
+            WeekPattern::FourFiveFour => [4, 5, 4],
+            WeekPattern::FiveFourFour => [5, 4, 4],
+        }
+    }
+}
+
+/// Configuration of a fiscal calendar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalCalendar {
+    start_month: Month,
+    weeks: Option<(WeekPattern, Day)>,
+}
+
+impl FiscalCalendar {
+    /// A fiscal calendar which starts on the first day of `start_month` and follows calendar
+    /// months
+    pub fn new(start_month: Month) -> FiscalCalendar {
+        FiscalCalendar {
+            start_month,
+            weeks: None,
+        }
+    }
+
+    /// Make the fiscal calendar consist of whole weeks
+    ///
+    /// Each fiscal year starts on the `week_start` day nearest to the first day of the start
+    /// month, so a fiscal year has either 52 or 53 weeks. Quarters have 13 weeks each, split into
+    /// periods according to `pattern`. A 53rd week is added to the last period of the year.
+    pub fn with_week_pattern(self, pattern: WeekPattern, week_start: Day) -> FiscalCalendar {
+        FiscalCalendar {
+            start_month: self.start_month,
+            weeks: Some((pattern, week_start)),
+        }
+    }
+
+    pub fn start_month(&self) -> &Month {
+        &self.start_month
+    }
+
+    pub fn week_pattern(&self) -> Option<WeekPattern> {
+        self.weeks.as_ref().map(|(pattern, _)| *pattern)
+    }
+
+    /// Get the first day of the fiscal year `date` lies in
+    pub fn start_of_year(&self, date: NaiveDate) -> Result<NaiveDate> {
+        let start_month: u32 = self.start_month.into();
+        let year = if date.month() >= start_month {
+            date.year()
+        } else {
+            date.year() - 1
+        };
+
+        if self.weeks.is_none() {
+            return self.nominal_start(year);
+        }
+
+        if self.start_of_nominal_year(year + 1)? <= date {
+            self.start_of_nominal_year(year + 1)
+        } else if self.start_of_nominal_year(year)? <= date {
+            self.start_of_nominal_year(year)
+        } else {
+            self.start_of_nominal_year(year - 1)
+        }
+    }
+
+    /// Get the last day of the fiscal year `date` lies in
+    pub fn end_of_year(&self, date: NaiveDate) -> Result<NaiveDate> {
+        let start = self.start_of_year(date)?;
+        let next_start = self.start_of_nominal_year(self.nominal_year_of_start(start) + 1)?;
+
+        next_start.pred_opt().ok_or(Error::OutOfRange(
+            next_start.year().into(),
+            next_start.month(),
+            next_start.day(),
+            0,
+            0,
+            0,
+        ))
+    }
+
+    /// Get the number of the fiscal year `date` lies in
+    ///
+    /// Fiscal years are named after the calendar year they nominally end in. For week based fiscal
+    /// calendars, the actual end might lie a few days after that year.
+    pub fn year(&self, date: NaiveDate) -> Result<i32> {
+        let start_month: u32 = self.start_month.into();
+        let nominal_year = self.nominal_year_of_start(self.start_of_year(date)?);
+        if start_month == 1 {
+            Ok(nominal_year)
+        } else {
+            Ok(nominal_year + 1)
+        }
+    }
+
+    /// Get the fiscal quarter (1 to 4) `date` lies in
+    pub fn quarter(&self, date: NaiveDate) -> Result<u32> {
+        self.period(date).map(|period| (period - 1) / 3 + 1)
+    }
+
+    /// Get the fiscal period (1 to 12) `date` lies in
+    ///
+    /// For calendar-month based fiscal calendars, this is the fiscal month. For week based fiscal
+    /// calendars, the periods are defined by the `WeekPattern`.
+    pub fn period(&self, date: NaiveDate) -> Result<u32> {
+        match self.weeks {
+            None => {
+                let start_month: u32 = self.start_month.into();
+                Ok((date.month() + 12 - start_month) % 12 + 1)
+            },
+            Some((pattern, _)) => {
+                let mut remaining = self.week(date)?;
+                for period in 1..12 {
+                    let weeks = pattern.weeks_per_period()[((period - 1) % 3) as usize];
+                    if remaining <= weeks {
+                        return Ok(period);
+                    }
+                    remaining -= weeks;
+                }
+                Ok(12)
+            },
+        }
+    }
+
+    /// Get the fiscal week `date` lies in, starting with week 1 at the start of the fiscal year
+    pub fn week(&self, date: NaiveDate) -> Result<u32> {
+        let start = self.start_of_year(date)?;
+        Ok(((date - start).num_days() / 7) as u32 + 1)
+    }
+
+    // The year in which the fiscal year starting at `start` nominally starts
+    //
+    // The week based start is at most three days away from the nominal start, which is the first
+    // day of the start month.
+    fn nominal_year_of_start(&self, start: NaiveDate) -> i32 {
+        match self.weeks {
+            None => start.year(),
+            Some(_) => (start + Duration::days(3)).year(),
+        }
+    }
+
+    fn nominal_start(&self, year: i32) -> Result<NaiveDate> {
+        let start_month: u32 = self.start_month.into();
+        NaiveDate::from_ymd_opt(year, start_month, 1).ok_or(Error::OutOfRange(year.into(), start_month, 1, 0, 0, 0))
+    }
+
+    // The week-based start of the fiscal year which nominally starts in `year`
+    fn start_of_nominal_year(&self, year: i32) -> Result<NaiveDate> {
+        let nominal = self.nominal_start(year)?;
+        match self.weeks {
+            None => Ok(nominal),
+            Some((_, ref week_start)) => {
+                let week_start: chrono::Weekday = (*week_start).into();
+                let diff = (week_start.num_days_from_monday() as i64 + 7
+                    - nominal.weekday().num_days_from_monday() as i64)
+                    % 7;
+                let offset = if diff > 3 { diff - 7 } else { diff };
+                Ok(nominal + Duration::days(offset))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::timetype::TimeType as TT;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_calendar_months_april() {
+        let cal = FiscalCalendar::new(Month::April);
+
+        assert_eq!(cal.start_of_year(ymd(2024, 4, 1)).unwrap(), ymd(2024, 4, 1));
+        assert_eq!(cal.start_of_year(ymd(2025, 3, 31)).unwrap(), ymd(2024, 4, 1));
+        assert_eq!(cal.end_of_year(ymd(2024, 4, 1)).unwrap(), ymd(2025, 3, 31));
+        assert_eq!(cal.end_of_year(ymd(2025, 1, 15)).unwrap(), ymd(2025, 3, 31));
+        assert_eq!(cal.year(ymd(2024, 12, 31)).unwrap(), 2025);
+        assert_eq!(cal.year(ymd(2024, 3, 31)).unwrap(), 2024);
+    }
+
+    #[test]
+    fn test_calendar_months_quarter_and_period() {
+        let cal = FiscalCalendar::new(Month::April);
+
+        assert_eq!(cal.quarter(ymd(2024, 4, 1)).unwrap(), 1);
+        assert_eq!(cal.quarter(ymd(2024, 6, 30)).unwrap(), 1);
+        assert_eq!(cal.quarter(ymd(2024, 7, 1)).unwrap(), 2);
+        assert_eq!(cal.quarter(ymd(2024, 12, 31)).unwrap(), 3);
+        assert_eq!(cal.quarter(ymd(2025, 1, 1)).unwrap(), 4);
+        assert_eq!(cal.quarter(ymd(2025, 3, 31)).unwrap(), 4);
+
+        assert_eq!(cal.period(ymd(2024, 4, 10)).unwrap(), 1);
+        assert_eq!(cal.period(ymd(2025, 3, 10)).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_calendar_months_january_is_calendar_year() {
+        let cal = FiscalCalendar::new(Month::January);
+
+        assert_eq!(cal.start_of_year(ymd(2024, 7, 1)).unwrap(), ymd(2024, 1, 1));
+        assert_eq!(cal.end_of_year(ymd(2024, 7, 1)).unwrap(), ymd(2024, 12, 31));
+        assert_eq!(cal.year(ymd(2024, 7, 1)).unwrap(), 2024);
+        assert_eq!(cal.quarter(ymd(2024, 7, 1)).unwrap(), 3);
+        assert_eq!(cal.week(ymd(2024, 1, 7)).unwrap(), 1);
+        assert_eq!(cal.week(ymd(2024, 1, 8)).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_week_pattern_start_and_end() {
+        // 2024-04-01 is a Monday, 2025-04-01 is a Tuesday
+        let cal = FiscalCalendar::new(Month::April).with_week_pattern(WeekPattern::FourFourFive, Day::Monday);
+
+        assert_eq!(cal.start_of_year(ymd(2024, 4, 1)).unwrap(), ymd(2024, 4, 1));
+        assert_eq!(cal.end_of_year(ymd(2024, 4, 1)).unwrap(), ymd(2025, 3, 30));
+        assert_eq!(cal.start_of_year(ymd(2025, 3, 30)).unwrap(), ymd(2024, 4, 1));
+        assert_eq!(cal.start_of_year(ymd(2025, 3, 31)).unwrap(), ymd(2025, 3, 31));
+        assert_eq!(cal.year(ymd(2025, 3, 31)).unwrap(), 2026);
+        assert_eq!(cal.week(ymd(2025, 3, 30)).unwrap(), 52);
+    }
+
+    #[test]
+    fn test_week_pattern_53_weeks() {
+        // 2026-01-01 is a Thursday, the nearest Monday is 2025-12-29
+        // 2027-01-01 is a Friday, the nearest Monday is 2027-01-04
+        let cal = FiscalCalendar::new(Month::January).with_week_pattern(WeekPattern::FourFourFive, Day::Monday);
+
+        assert_eq!(cal.start_of_year(ymd(2026, 6, 1)).unwrap(), ymd(2025, 12, 29));
+        assert_eq!(cal.start_of_year(ymd(2025, 12, 30)).unwrap(), ymd(2025, 12, 29));
+        assert_eq!(cal.end_of_year(ymd(2025, 12, 29)).unwrap(), ymd(2027, 1, 3));
+        assert_eq!(cal.year(ymd(2025, 12, 30)).unwrap(), 2026);
+        assert_eq!(cal.year(ymd(2027, 1, 3)).unwrap(), 2026);
+        assert_eq!(cal.week(ymd(2027, 1, 3)).unwrap(), 53);
+        assert_eq!(cal.quarter(ymd(2027, 1, 3)).unwrap(), 4);
+        assert_eq!(cal.period(ymd(2027, 1, 3)).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_week_pattern_periods() {
+        let start = ymd(2024, 4, 1);
+
+        let cal = FiscalCalendar::new(Month::April).with_week_pattern(WeekPattern::FourFourFive, Day::Monday);
+        assert_eq!(cal.period(start + Duration::weeks(3)).unwrap(), 1);
+        assert_eq!(cal.period(start + Duration::weeks(4)).unwrap(), 2);
+        assert_eq!(cal.period(start + Duration::weeks(8)).unwrap(), 3);
+        assert_eq!(cal.period(start + Duration::weeks(12)).unwrap(), 3);
+        assert_eq!(cal.period(start + Duration::weeks(13)).unwrap(), 4);
+        assert_eq!(cal.quarter(start + Duration::weeks(13)).unwrap(), 2);
+
+        let cal = FiscalCalendar::new(Month::April).with_week_pattern(WeekPattern::FiveFourFour, Day::Monday);
+        assert_eq!(cal.period(start + Duration::weeks(4)).unwrap(), 1);
+        assert_eq!(cal.period(start + Duration::weeks(5)).unwrap(), 2);
+        assert_eq!(cal.period(start + Duration::weeks(9)).unwrap(), 3);
+
+        let cal = FiscalCalendar::new(Month::April).with_week_pattern(WeekPattern::FourFiveFour, Day::Monday);
+        assert_eq!(cal.period(start + Duration::weeks(8)).unwrap(), 2);
+        assert_eq!(cal.period(start + Duration::weeks(9)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_timetype_interface() {
+        let cal = FiscalCalendar::new(Month::April);
+        let date = TT::moment(ymd(2024, 11, 5).and_hms_opt(13, 0, 0).unwrap());
+
+        assert_eq!(
+            date.start_of_fiscal_year(&cal).unwrap(),
+            TT::moment(ymd(2024, 4, 1).and_hms_opt(0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            date.end_of_fiscal_year(&cal).unwrap(),
+            TT::moment(ymd(2025, 3, 31).and_hms_opt(0, 0, 0).unwrap())
+        );
+        assert_eq!(date.fiscal_year(&cal).unwrap(), 2025);
+        assert_eq!(date.fiscal_quarter(&cal).unwrap(), 3);
+        assert_eq!(date.fiscal_week(&cal).unwrap(), 32);
+
+        let date = date + TT::months(5);
+        assert_eq!(date.fiscal_year(&cal).unwrap(), 2026);
+
+        assert!(TT::days(1).fiscal_quarter(&cal).is_err());
+    }
+}
@@ -1,14 +1,21 @@
-#[cfg(feature = "with-filters")]
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
 #[cfg(feature = "with-filters")]
 use filters::filter::Filter;
 #[cfg(feature = "with-filters")]
 use filters::filter::IntoFilter;
 
-#[cfg(feature = "with-filters")]
+use crate::error::Error;
+use crate::error::Result;
 use crate::timetype::TimeType;
+use crate::util::get_num_of_days_in_month;
+use crate::util::is_leap_year;
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Day {
     Monday,
     Tuesday,
@@ -33,7 +40,13 @@ impl From<Day> for chrono::Weekday {
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+impl From<chrono::Weekday> for Day {
+    fn from(val: chrono::Weekday) -> Self {
+        DAYS[val.num_days_from_monday() as usize]
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Month {
     January,
     February,
@@ -68,14 +81,472 @@ impl From<Month> for u32 {
     }
 }
 
+/// Fails with `Error::InvalidMonth` for numbers outside of 1 to 12
+impl TryFrom<u32> for Month {
+    type Error = Error;
+
+    fn try_from(val: u32) -> Result<Self> {
+        match val {
+            1..=12 => Ok(MONTHS[val as usize - 1]),
+            _ => Err(Error::InvalidMonth(val)),
+        }
+    }
+}
+
+impl From<chrono::Month> for Month {
+    fn from(val: chrono::Month) -> Self {
+        MONTHS[val.number_from_month() as usize - 1]
+    }
+}
+
+impl From<Month> for chrono::Month {
+    fn from(val: Month) -> Self {
+        match val {
+            Month::January => chrono::Month::January,
+            Month::February => chrono::Month::February,
+            Month::March => chrono::Month::March,
+            Month::April => chrono::Month::April,
+            Month::May => chrono::Month::May,
+            Month::June => chrono::Month::June,
+            Month::July => chrono::Month::July,
+            Month::August => chrono::Month::August,
+            Month::September => chrono::Month::September,
+            Month::October => chrono::Month::October,
+            Month::November => chrono::Month::November,
+            Month::December => chrono::Month::December,
+        }
+    }
+}
+
+/// The names of days and months in some language, for parsing and displaying indicators
+///
+/// Custom locales can be defined by filling in the tables, which are ordered from Monday and
+/// January respectively.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Locale {
+    pub days: [&'static str; 7],
+    pub day_abbreviations: [&'static str; 7],
+    pub months: [&'static str; 12],
+    pub month_abbreviations: [&'static str; 12],
+}
+
+impl Locale {
+    pub const ENGLISH: Locale = Locale {
+        days: [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        day_abbreviations: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        months: [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        month_abbreviations: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+    };
+
+    pub const GERMAN: Locale = Locale {
+        days: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        day_abbreviations: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        month_abbreviations: [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+    };
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::ENGLISH
+    }
+}
+
+// Find the 1-based position of `s` in the tables, by name, abbreviation or number
+fn lookup(s: &str, names: &[&str], abbreviations: &[&str]) -> Option<usize> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<usize>() {
+        return Some(n).filter(|n| (1..=names.len()).contains(n));
+    }
+
+    let s = s.to_lowercase();
+    names
+        .iter()
+        .position(|name| name.to_lowercase() == s)
+        .or_else(|| abbreviations.iter().position(|abbr| abbr.to_lowercase() == s))
+        .map(|i| i + 1)
+}
+
+fn parse_error(what: &str, s: &str) -> Error {
+    Error::Parse {
+        position: 0,
+        message: format!("unknown {}: '{}'", what, s),
+    }
+}
+
+const DAYS: [Day; 7] = [
+    Day::Monday,
+    Day::Tuesday,
+    Day::Wednesday,
+    Day::Thursday,
+    Day::Friday,
+    Day::Saturday,
+    Day::Sunday,
+];
+
+const MONTHS: [Month; 12] = [
+    Month::January,
+    Month::February,
+    Month::March,
+    Month::April,
+    Month::May,
+    Month::June,
+    Month::July,
+    Month::August,
+    Month::September,
+    Month::October,
+    Month::November,
+    Month::December,
+];
+
+impl Day {
+    /// All days of the week, starting with Monday
+    pub fn all() -> [Day; 7] {
+        DAYS
+    }
+
+    /// The following day, Sunday being followed by Monday
+    pub fn succ(self) -> Day {
+        DAYS[(self.index() + 1) % 7]
+    }
+
+    /// The preceding day, Monday being preceded by Sunday
+    pub fn pred(self) -> Day {
+        DAYS[(self.index() + 6) % 7]
+    }
+
+    /// Parse a day by its full name, abbreviation or number (1 being Monday) in `locale`
+    ///
+    /// Names are matched case-insensitively.
+    pub fn parse_in(s: &str, locale: &Locale) -> Result<Day> {
+        lookup(s, &locale.days, &locale.day_abbreviations)
+            .map(|n| DAYS[n - 1])
+            .ok_or_else(|| parse_error("day", s))
+    }
+
+    /// The full name of the day in `locale`
+    pub fn name_in(&self, locale: &Locale) -> &'static str {
+        locale.days[self.index()]
+    }
+
+    /// The abbreviated name of the day in `locale`
+    pub fn abbreviation_in(&self, locale: &Locale) -> &'static str {
+        locale.day_abbreviations[self.index()]
+    }
+
+    fn index(&self) -> usize {
+        chrono::Weekday::from(*self).num_days_from_monday() as usize
+    }
+}
+
+/// Parses English names, see `Day::parse_in()`
+impl FromStr for Day {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Day> {
+        Day::parse_in(s, &Locale::ENGLISH)
+    }
+}
+
+/// Displays the full English name
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name_in(&Locale::ENGLISH))
+    }
+}
+
+impl Month {
+    /// All months of the year, starting with January
+    pub fn all() -> [Month; 12] {
+        MONTHS
+    }
+
+    /// The following month, December being followed by January
+    pub fn succ(self) -> Month {
+        MONTHS[(self.index() + 1) % 12]
+    }
+
+    /// The preceding month, January being preceded by December
+    pub fn pred(self) -> Month {
+        MONTHS[(self.index() + 11) % 12]
+    }
+
+    /// The number of days of the month in `year`
+    pub fn num_days(self, year: i32) -> u32 {
+        get_num_of_days_in_month(year as i64, u32::from(self) as i64) as u32
+    }
+
+    /// Parse a month by its full name, abbreviation or number (1 being January) in `locale`
+    ///
+    /// Names are matched case-insensitively.
+    pub fn parse_in(s: &str, locale: &Locale) -> Result<Month> {
+        lookup(s, &locale.months, &locale.month_abbreviations)
+            .map(|n| MONTHS[n - 1])
+            .ok_or_else(|| parse_error("month", s))
+    }
+
+    /// The full name of the month in `locale`
+    pub fn name_in(&self, locale: &Locale) -> &'static str {
+        locale.months[self.index()]
+    }
+
+    /// The abbreviated name of the month in `locale`
+    pub fn abbreviation_in(&self, locale: &Locale) -> &'static str {
+        locale.month_abbreviations[self.index()]
+    }
+
+    fn index(&self) -> usize {
+        u32::from(*self) as usize - 1
+    }
+}
+
+/// Parses English names, see `Month::parse_in()`
+impl FromStr for Month {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Month> {
+        Month::parse_in(s, &Locale::ENGLISH)
+    }
+}
+
+/// Displays the full English name
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name_in(&Locale::ENGLISH))
+    }
+}
+
+/// A day of the month, counted from 1, or the last day of whatever month it is
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum DayOfMonth {
+    Nth(u32),
+    Last,
+}
+
+/// An hour of the day, from 0 to 23
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Hour(pub u32);
+
+/// A minute of the hour, from 0 to 59
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Minute(pub u32);
+
+/// A calendar year
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Year(pub i32);
+
+impl Year {
+    pub fn is_leap(self) -> bool {
+        is_leap_year(self.0)
+    }
+
+    /// The number of days in the year, 365 or 366
+    pub fn num_days(self) -> u32 {
+        if self.is_leap() {
+            366
+        } else {
+            365
+        }
+    }
+}
+
+/// An ISO 8601 week number, from 1 to 53
+///
+/// The first week of a year is the one containing its first Thursday, so the first days of
+/// January can belong to week 52 or 53 of the previous year.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct IsoWeek(pub u32);
+
+/// A day of the year, from 1 to 366
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DayOfYear(pub u32);
+
+/// A moment bounding a range, either included in the range or not
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum Bound {
+    Inclusive(NaiveDateTime),
+    Exclusive(NaiveDateTime),
+}
+
+impl Bound {
+    pub fn moment(&self) -> &NaiveDateTime {
+        match self {
+            Bound::Inclusive(m) | Bound::Exclusive(m) => m,
+        }
+    }
+
+    /// Check whether `moment` lies before the bound, or on it if inclusive
+    pub fn is_above(&self, moment: &NaiveDateTime) -> bool {
+        match self {
+            Bound::Inclusive(m) => moment <= m,
+            Bound::Exclusive(m) => moment < m,
+        }
+    }
+
+    /// Check whether `moment` lies after the bound, or on it if inclusive
+    pub fn is_below(&self, moment: &NaiveDateTime) -> bool {
+        match self {
+            Bound::Inclusive(m) => moment >= m,
+            Bound::Exclusive(m) => moment > m,
+        }
+    }
+}
+
+/// All moments before a bound
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Before(pub Bound);
+
+/// All moments after a bound
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct After(pub Bound);
+
+/// All moments between two bounds
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Between {
+    start: Bound,
+    end: Bound,
+}
+
+impl Between {
+    /// Returns an error if the end lies before the start
+    pub fn new(start: Bound, end: Bound) -> Result<Between> {
+        if end.moment() < start.moment() {
+            Err(Error::InvalidInterval(*start.moment(), *end.moment()))
+        } else {
+            Ok(Between { start, end })
+        }
+    }
+
+    /// Both `start` and `end` are included
+    pub fn inclusive(start: NaiveDateTime, end: NaiveDateTime) -> Result<Between> {
+        Between::new(Bound::Inclusive(start), Bound::Inclusive(end))
+    }
+
+    /// Neither `start` nor `end` are included
+    pub fn exclusive(start: NaiveDateTime, end: NaiveDateTime) -> Result<Between> {
+        Between::new(Bound::Exclusive(start), Bound::Exclusive(end))
+    }
+
+    pub fn start(&self) -> &Bound {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Bound {
+        &self.end
+    }
+
+    pub fn contains(&self, moment: &NaiveDateTime) -> bool {
+        self.start.is_below(moment) && self.end.is_above(moment)
+    }
+}
+
+/// A well-known point in time, relative to some moment
+///
+/// Marks are used to bound an iteration (`iter::Occurrences::until()`) and to check moments
+/// against (`TimeType::is()`). As a bound, the start and end marks always refer to the next
+/// turn of a year or month, so a start mark and its end mark resolve to the same moment.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Mark {
+    /// Matches January 1st, resolves to January 1st 00:00 of the next year
+    StartOfYear,
+
+    /// Matches December 31st, resolves to January 1st 00:00 of the next year, so that an
+    /// exclusive bound includes the whole last day of the year
+    EndOfYear,
+
+    /// Matches the 1st of a month, resolves to the 1st 00:00 of the next month
+    StartOfMonth,
+
+    /// Matches the last day of a month, resolves to the 1st 00:00 of the next month, so that an
+    /// exclusive bound includes the whole last day of the month
+    EndOfMonth,
+
+    /// A fixed moment, the expression must evaluate to a `TimeType::Moment`
+    Moment(TimeType),
+}
+
+impl Mark {
+    /// The moment the mark refers to, seen from `from`
+    ///
+    /// Start marks resolve to the next start of a year or month after `from`. End marks resolve
+    /// to the same moment, which is right after the end of the year or month of `from`, so an
+    /// exclusive bound still includes the whole last day.
+    pub fn resolve(&self, from: &NaiveDateTime) -> Result<NaiveDateTime> {
+        let (y, m) = (from.year(), from.month());
+        let start_of = |y: i32, m: u32| {
+            NaiveDate::from_ymd_opt(y, m, 1)
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .ok_or(Error::OutOfRange(y as i64, m, 1, 0, 0, 0))
+        };
+
+        let tt = match self {
+            Mark::StartOfYear | Mark::EndOfYear => return start_of(y + 1, 1),
+            Mark::StartOfMonth | Mark::EndOfMonth if m == 12 => return start_of(y + 1, 1),
+            Mark::StartOfMonth | Mark::EndOfMonth => return start_of(y, m + 1),
+            Mark::Moment(tt) => tt.clone(),
+        };
+
+        match tt.calculate()? {
+            TimeType::Moment(m) => Ok(m),
+            other => Err(Error::NotAMoment(other)),
+        }
+    }
+}
+
 #[cfg(feature = "with-filters")]
 pub struct DayFilter(Day);
 
 #[cfg(feature = "with-filters")]
 impl Filter<TimeType> for DayFilter {
     fn filter(&self, tt: &TimeType) -> bool {
-        tt.get_moment()
-            .map(|mom| mom.weekday() == self.0.clone().into())
+        tt.get_calendar_date()
+            .map(|date| date.weekday() == self.0.into())
             .unwrap_or(false)
     }
 }
@@ -95,8 +566,8 @@ pub struct MonthFilter(Month);
 #[cfg(feature = "with-filters")]
 impl Filter<TimeType> for MonthFilter {
     fn filter(&self, tt: &TimeType) -> bool {
-        tt.get_moment()
-            .map(|mom| mom.month() == self.0.clone().into())
+        tt.get_calendar_date()
+            .map(|date| date.month() == self.0.into())
             .unwrap_or(false)
     }
 }
@@ -109,3 +580,144 @@ impl IntoFilter<TimeType> for Month {
         MonthFilter(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!("Saturday".parse::<Day>().unwrap(), Day::Saturday);
+        assert_eq!("sat".parse::<Day>().unwrap(), Day::Saturday);
+        assert_eq!(" SUN ".parse::<Day>().unwrap(), Day::Sunday);
+        assert_eq!("1".parse::<Day>().unwrap(), Day::Monday);
+        assert_eq!("7".parse::<Day>().unwrap(), Day::Sunday);
+        assert!("0".parse::<Day>().is_err());
+        assert!("8".parse::<Day>().is_err());
+        assert!("Sa".parse::<Day>().is_err());
+        assert!("".parse::<Day>().is_err());
+    }
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!("march".parse::<Month>().unwrap(), Month::March);
+        assert_eq!("Dec".parse::<Month>().unwrap(), Month::December);
+        assert_eq!("02".parse::<Month>().unwrap(), Month::February);
+        assert!("13".parse::<Month>().is_err());
+        assert!("Mär".parse::<Month>().is_err());
+
+        let e = "Smarch".parse::<Month>().unwrap_err();
+        assert_eq!(e.code(), "parse/syntax");
+    }
+
+    #[test]
+    fn test_parse_german() {
+        assert_eq!(Day::parse_in("Sa", &Locale::GERMAN).unwrap(), Day::Saturday);
+        assert_eq!(Day::parse_in("sonntag", &Locale::GERMAN).unwrap(), Day::Sunday);
+        assert_eq!(Month::parse_in("MÄRZ", &Locale::GERMAN).unwrap(), Month::March);
+        assert_eq!(Month::parse_in("okt", &Locale::GERMAN).unwrap(), Month::October);
+        assert!(Month::parse_in("Oct", &Locale::GERMAN).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Day::Wednesday.to_string(), "Wednesday");
+        assert_eq!(Month::May.to_string(), "May");
+        assert_eq!(Day::Wednesday.name_in(&Locale::GERMAN), "Mittwoch");
+        assert_eq!(Day::Wednesday.abbreviation_in(&Locale::GERMAN), "Mi");
+        assert_eq!(Month::March.abbreviation_in(&Locale::ENGLISH), "Mar");
+        assert_eq!(Month::December.name_in(&Locale::GERMAN), "Dezember");
+    }
+
+    #[test]
+    fn test_chrono_conversions() {
+        for day in Day::all() {
+            assert_eq!(Day::from(chrono::Weekday::from(day)), day);
+        }
+        for month in Month::all() {
+            assert_eq!(Month::try_from(u32::from(month)).unwrap(), month);
+            assert_eq!(Month::from(chrono::Month::from(month)), month);
+        }
+        assert_eq!(Day::from(chrono::Weekday::Sun), Day::Sunday);
+        assert_eq!(Month::try_from(0), Err(Error::InvalidMonth(0)));
+        assert_eq!(Month::try_from(13), Err(Error::InvalidMonth(13)));
+    }
+
+    #[test]
+    fn test_succ_pred() {
+        assert_eq!(Day::Monday.succ(), Day::Tuesday);
+        assert_eq!(Day::Sunday.succ(), Day::Monday);
+        assert_eq!(Day::Monday.pred(), Day::Sunday);
+        assert_eq!(Month::December.succ(), Month::January);
+        assert_eq!(Month::January.pred(), Month::December);
+        assert_eq!(Month::June.pred(), Month::May);
+
+        for day in Day::all() {
+            assert_eq!(day.succ().pred(), day);
+        }
+        for month in Month::all() {
+            assert_eq!(month.pred().succ(), month);
+        }
+    }
+
+    #[test]
+    fn test_num_days() {
+        assert_eq!(Month::February.num_days(2024), 29);
+        assert_eq!(Month::February.num_days(1900), 28);
+        assert_eq!(Month::April.num_days(2023), 30);
+        assert_eq!(
+            Month::all().iter().map(|m| m.num_days(2024)).sum::<u32>(),
+            Year(2024).num_days()
+        );
+        assert_eq!(
+            Month::all().iter().map(|m| m.num_days(2023)).sum::<u32>(),
+            Year(2023).num_days()
+        );
+        assert!(Year(2000).is_leap());
+        assert!(!Year(2100).is_leap());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for locale in [Locale::ENGLISH, Locale::GERMAN] {
+            for day in Day::all().iter() {
+                assert_eq!(&Day::parse_in(day.name_in(&locale), &locale).unwrap(), day);
+                assert_eq!(&Day::parse_in(day.abbreviation_in(&locale), &locale).unwrap(), day);
+            }
+            for month in Month::all().iter() {
+                assert_eq!(&Month::parse_in(month.name_in(&locale), &locale).unwrap(), month);
+                assert_eq!(
+                    &Month::parse_in(month.abbreviation_in(&locale), &locale).unwrap(),
+                    month
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_marks_resolve_to_the_next_turn() {
+        let from = NaiveDate::from_ymd_opt(2024, 12, 15)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let next_year = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+
+        for mark in [Mark::StartOfYear, Mark::EndOfYear, Mark::StartOfMonth, Mark::EndOfMonth] {
+            assert_eq!(mark.resolve(&from).unwrap(), next_year);
+        }
+
+        let from = NaiveDate::from_ymd_opt(2024, 2, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let march = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(Mark::StartOfMonth.resolve(&from).unwrap(), march);
+        assert_eq!(Mark::EndOfMonth.resolve(&from).unwrap(), march);
+    }
+}
@@ -0,0 +1,412 @@
+//! The module containing interval types
+//!
+//! An `Interval` is a half-open range `[start, end)` between two moments. An `IntervalSet` is a
+//! normalized set of such intervals: sorted, non-overlapping and without empty members. Adjacent
+//! intervals are merged, so `[09:00, 10:00)` and `[10:00, 11:00)` become `[09:00, 11:00)`.
+//!
+//! Interval sets can be built from any occurrence iterator (like `iter::Iter`) plus a length,
+//! which makes free/busy and availability calculations possible.
+
+use chrono::Duration;
+use chrono::NaiveDateTime;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::timetype::TimeType;
+
+/// A half-open interval `[start, end)` between two moments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl Interval {
+    /// Create a new interval
+    ///
+    /// Returns an error if `end` lies before `start`. An interval with `start == end` is valid,
+    /// but empty.
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Result<Interval> {
+        if end < start {
+            Err(Error::InvalidInterval(start, end))
+        } else {
+            Ok(Interval { start, end })
+        }
+    }
+
+    /// Create a new interval from a start and a length
+    ///
+    /// Both arguments are calculated. The start must evaluate to a `TimeType::Moment`, the length
+    /// must evaluate to an amount.
+    pub fn from_start_and_length(start: TimeType, length: TimeType) -> Result<Interval> {
+        let start = start.calculate()?;
+        let start = *start.get_moment().ok_or_else(|| Error::NotAMoment(start.clone()))?;
+
+        let length = length.calculate()?;
+        if !length.is_a_amount() {
+            return Err(Error::NotAnAmount(length));
+        }
+
+        let end = (TimeType::moment(start) + length).calculate()?;
+        let end = *end.get_moment().ok_or_else(|| Error::NotAMoment(end.clone()))?;
+
+        Interval::new(start, end)
+    }
+
+    pub fn start(&self) -> &NaiveDateTime {
+        &self.start
+    }
+
+    pub fn end(&self) -> &NaiveDateTime {
+        &self.end
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Check whether a moment lies inside of the interval
+    ///
+    /// The start is included, the end is not.
+    pub fn contains(&self, moment: &NaiveDateTime) -> bool {
+        self.start <= *moment && *moment < self.end
+    }
+
+    /// Check whether two intervals share at least one moment
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Get the overlapping part of two intervals, if there is one
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if self.overlaps(other) {
+            Some(Interval {
+                start: std::cmp::max(self.start, other.start),
+                end: std::cmp::min(self.end, other.end),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A normalized set of intervals
+///
+/// The intervals inside the set are always sorted, non-overlapping and non-empty. Touching
+/// intervals are merged into one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet(Vec<Interval>);
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet(Vec::new())
+    }
+
+    /// Build an interval set from occurrences and a length for each occurrence
+    ///
+    /// Each item of the iterator is calculated and must result in a `TimeType::Moment`, which is
+    /// used as the start of an interval of `length`. The first error of the iterator is returned.
+    ///
+    /// # Warning
+    ///
+    /// The iterator is consumed completely, so it must be bounded (for example with
+    /// `iter::Until::until()` or `iter::Times::times()`).
+    pub fn from_occurrences<I>(iter: I, length: TimeType) -> Result<IntervalSet>
+    where
+        I: Iterator<Item = Result<TimeType>>,
+    {
+        iter.map(|occurrence| Interval::from_start_and_length(occurrence?, length.clone()))
+            .collect()
+    }
+
+    /// Add an interval to the set, merging it with the intervals it overlaps or touches
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // index of the first interval which ends at or after the new one starts
+        let first = self.0.partition_point(|iv| iv.end < interval.start);
+        // index after the last interval which starts at or before the new one ends
+        let last = self.0.partition_point(|iv| iv.start <= interval.end);
+
+        let merged = self.0[first..last].iter().fold(interval, |acc, iv| Interval {
+            start: std::cmp::min(acc.start, iv.start),
+            end: std::cmp::max(acc.end, iv.end),
+        });
+
+        self.0.splice(first..last, std::iter::once(merged));
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check whether a moment lies inside of any interval of the set
+    pub fn contains(&self, moment: &NaiveDateTime) -> bool {
+        let idx = self.0.partition_point(|iv| iv.end <= *moment);
+        self.0.get(idx).map(|iv| iv.contains(moment)).unwrap_or(false)
+    }
+
+    /// The summed up duration of all intervals in the set
+    pub fn total_duration(&self) -> Duration {
+        self.0.iter().fold(Duration::zero(), |acc, iv| acc + iv.duration())
+    }
+
+    /// All moments which are in `self` or in `other`
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for iv in other.iter() {
+            result.insert(*iv);
+        }
+        result
+    }
+
+    /// All moments which are in `self` and in `other`
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.0.len() && j < other.0.len() {
+            let (a, b) = (&self.0[i], &other.0[j]);
+
+            if let Some(iv) = a.intersection(b) {
+                result.push(iv);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet(result)
+    }
+
+    /// All moments which are in `self` but not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let mut j = 0;
+
+        for iv in self.0.iter() {
+            let mut start = iv.start;
+
+            // skip everything from `other` which ends before the current interval
+            while j < other.0.len() && other.0[j].end <= start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.0.len() && other.0[k].start < iv.end {
+                let cut = &other.0[k];
+                if cut.start > start {
+                    result.push(Interval { start, end: cut.start });
+                }
+                start = std::cmp::max(start, cut.end);
+                k += 1;
+            }
+
+            if start < iv.end {
+                result.push(Interval { start, end: iv.end });
+            }
+        }
+
+        IntervalSet(result)
+    }
+
+    /// All moments inside of `window` which are not in `self`
+    pub fn complement(&self, window: &Interval) -> IntervalSet {
+        let mut whole = IntervalSet::new();
+        whole.insert(*window);
+        whole.difference(self)
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for iv in iter {
+            set.insert(iv);
+        }
+        set
+    }
+}
+
+impl IntoIterator for IntervalSet {
+    type Item = Interval;
+    type IntoIter = std::vec::IntoIter<Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a Interval;
+    type IntoIter = std::slice::Iter<'a, Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicator::Day;
+    use crate::iter::extensions::Daily;
+    use crate::iter::Until;
+    use crate::iter::WithoutFilter;
+    use crate::util::tests::ymd_hms;
+
+    fn iv(from: (u32, u32), to: (u32, u32)) -> Interval {
+        Interval::new(
+            ymd_hms(2024, 1, from.0, from.1, 0, 0),
+            ymd_hms(2024, 1, to.0, to.1, 0, 0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_interval_new_invalid() {
+        let res = Interval::new(ymd_hms(2024, 1, 2, 0, 0, 0), ymd_hms(2024, 1, 1, 0, 0, 0));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_interval_from_start_and_length() {
+        let res = Interval::from_start_and_length(TimeType::moment(ymd_hms(2024, 1, 31, 22, 0, 0)), TimeType::hours(4));
+        assert_eq!(
+            res.unwrap(),
+            Interval::new(ymd_hms(2024, 1, 31, 22, 0, 0), ymd_hms(2024, 2, 1, 2, 0, 0)).unwrap()
+        );
+
+        let res = Interval::from_start_and_length(TimeType::hours(1), TimeType::hours(4));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_insert_merges_overlapping_and_touching() {
+        let mut set = IntervalSet::new();
+        set.insert(iv((1, 9), (1, 10)));
+        set.insert(iv((1, 12), (1, 13)));
+        set.insert(iv((1, 10), (1, 11)));
+        assert_eq!(set.intervals(), &[iv((1, 9), (1, 11)), iv((1, 12), (1, 13))]);
+
+        set.insert(iv((1, 8), (1, 14)));
+        assert_eq!(set.intervals(), &[iv((1, 8), (1, 14))]);
+
+        set.insert(iv((1, 20), (1, 20)));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_union() {
+        let a: IntervalSet = vec![iv((1, 9), (1, 11)), iv((1, 14), (1, 15))].into_iter().collect();
+        let b: IntervalSet = vec![iv((1, 10), (1, 12)), iv((1, 16), (1, 17))].into_iter().collect();
+
+        assert_eq!(
+            a.union(&b).intervals(),
+            &[iv((1, 9), (1, 12)), iv((1, 14), (1, 15)), iv((1, 16), (1, 17))]
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a: IntervalSet = vec![iv((1, 9), (1, 12)), iv((1, 14), (1, 18))].into_iter().collect();
+        let b: IntervalSet = vec![iv((1, 11), (1, 15)), iv((1, 17), (1, 20))].into_iter().collect();
+
+        assert_eq!(
+            a.intersection(&b).intervals(),
+            &[iv((1, 11), (1, 12)), iv((1, 14), (1, 15)), iv((1, 17), (1, 18))]
+        );
+    }
+
+    #[test]
+    fn test_difference() {
+        let a: IntervalSet = vec![iv((1, 9), (1, 17))].into_iter().collect();
+        let b: IntervalSet = vec![iv((1, 8), (1, 10)), iv((1, 12), (1, 13)), iv((1, 16), (1, 18))]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            a.difference(&b).intervals(),
+            &[iv((1, 10), (1, 12)), iv((1, 13), (1, 16))]
+        );
+        assert!(b.difference(&b).is_empty());
+    }
+
+    #[test]
+    fn test_complement() {
+        let a: IntervalSet = vec![iv((1, 9), (1, 12)), iv((1, 14), (1, 15))].into_iter().collect();
+
+        assert_eq!(
+            a.complement(&iv((1, 0), (2, 0))).intervals(),
+            &[iv((1, 0), (1, 9)), iv((1, 12), (1, 14)), iv((1, 15), (2, 0))]
+        );
+    }
+
+    #[test]
+    fn test_contains_and_total_duration() {
+        let a: IntervalSet = vec![iv((1, 9), (1, 12)), iv((1, 14), (1, 15))].into_iter().collect();
+
+        assert!(a.contains(&ymd_hms(2024, 1, 1, 9, 0, 0)));
+        assert!(a.contains(&ymd_hms(2024, 1, 1, 14, 30, 0)));
+        assert!(!a.contains(&ymd_hms(2024, 1, 1, 12, 0, 0)));
+        assert!(!a.contains(&ymd_hms(2024, 1, 1, 15, 0, 0)));
+        assert_eq!(a.total_duration(), Duration::hours(4));
+    }
+
+    #[test]
+    fn test_availability() {
+        // every weekday 09:00-17:00, minus a holiday, minus a booked meeting
+        let working_hours = TimeType::moment(ymd_hms(2024, 1, 1, 9, 0, 0))
+            .daily(1)
+            .unwrap()
+            .until(ymd_hms(2024, 1, 8, 0, 0, 0))
+            .without(Day::Saturday)
+            .without(Day::Sunday);
+
+        let working_hours = IntervalSet::from_occurrences(working_hours, TimeType::hours(8)).unwrap();
+        let holidays = IntervalSet::from(iv((1, 0), (2, 0)));
+        let meetings = IntervalSet::from(iv((2, 10), (2, 11)));
+
+        let available = working_hours.difference(&holidays).difference(&meetings);
+
+        assert_eq!(
+            available.intervals(),
+            &[
+                iv((2, 9), (2, 10)),
+                iv((2, 11), (2, 17)),
+                iv((3, 9), (3, 17)),
+                iv((4, 9), (4, 17)),
+                iv((5, 9), (5, 17)),
+            ]
+        );
+    }
+}
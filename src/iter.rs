@@ -1,18 +1,40 @@
 //! The module containing the iterator types
 //!
 
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::Datelike;
+use chrono::Duration;
 use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Timelike;
 
 use crate::error::Error;
+use crate::error::Operation;
 use crate::error::Result;
+use crate::indicator::Bound;
+use crate::indicator::Mark;
+use crate::interval::Interval;
+use crate::matcher::IntoMatcher;
 use crate::matcher::Matcher;
 use crate::timetype::TimeType;
+use crate::util::add_months_clamped;
+use crate::util::get_num_of_days_in_month;
+
+/// Fixed increments up to this many seconds are jumped in closed form when seeking forward
+///
+/// Bigger ones can carry over more than one month in a single step, see `util::adjust_times_add`.
+const MAX_JUMPABLE_SECONDS: i64 = 28 * 24 * 60 * 60;
 
 #[derive(Debug)]
 pub struct Iter {
     base: TimeType,
     increment: TimeType,
     had_first: bool,
+    backwards: bool,
+    anchor: Option<NaiveDateTime>,
+    index: i64,
 }
 
 // An iterator for creating new TimeType instances based on a base-date plus some increment value
@@ -25,12 +47,15 @@ pub struct Iter {
 impl Iter {
     pub fn build(base: NaiveDateTime, inc: TimeType) -> Result<Iter> {
         if !inc.is_a_amount() {
-            Err(Error::ArgumentErrorNotAnAmount(inc))
+            Err(Error::NotAnAmount(inc))
         } else {
             Ok(Iter {
                 base: TimeType::moment(base),
                 increment: inc,
                 had_first: false,
+                backwards: false,
+                anchor: None,
+                index: 0,
             })
         }
     }
@@ -39,18 +64,263 @@ impl Iter {
         &self.increment
     }
 
+    /// Iterate backwards in time, subtracting the increment in each `next()` call
+    ///
+    /// Increments of months or years are anchored, see `Iter::anchored()`. Subtracting them one by
+    /// one would clamp each result and drift: from March 31st to February 29th and January 29th.
+    ///
+    /// Combine with `Since::since()` to bound the iteration.
+    pub fn backwards(mut self) -> Iter {
+        self.backwards = true;
+        match self.increment.clone().calculate() {
+            Ok(TimeType::Months(_)) | Ok(TimeType::Years(_)) if !self.is_anchored() => self.anchored(),
+            _ => self,
+        }
+    }
+
+    pub fn is_backwards(&self) -> bool {
+        self.backwards
+    }
+
+    /// Compute the nth occurrence as `base + n * increment` instead of adding to the previous one
+    ///
+    /// Months and years are clamped to the last day of shorter months, without drifting: monthly
+    /// from January 31st yields January 31st, February 28th or 29th, March 31st, April 30th and so
+    /// on. Without anchoring, the increment is added cumulatively and the series overflows into
+    /// March and stays at the start of the month.
+    pub fn anchored(mut self) -> Iter {
+        self.anchor = self.base.get_moment().copied();
+        self.index = 0;
+        self
+    }
+
+    pub fn is_anchored(&self) -> bool {
+        self.anchor.is_some()
+    }
+
     /// Skip one `next()` call
     pub fn skip(&mut self) -> Result<()> {
+        if let Some(anchor) = self.anchor {
+            let index = self
+                .index
+                .checked_add(1)
+                .ok_or_else(|| self.overflow(&self.increment))?;
+            return self.move_to(anchor, index);
+        }
         self.base += self.increment.clone();
         self.recalculate()
     }
 
     /// Redo the latest `next()` call with the next `next()` call
     pub fn rollback(&mut self) -> Result<()> {
+        if let Some(anchor) = self.anchor {
+            let index = self
+                .index
+                .checked_sub(1)
+                .ok_or_else(|| self.overflow(&self.increment))?;
+            return self.move_to(anchor, index);
+        }
         self.base -= self.increment.clone();
         self.recalculate()
     }
 
+    /// Advance the iterator so that `next()` yields the first occurrence after `moment`
+    ///
+    /// When iterating backwards, this is the first occurrence before `moment`. If the next
+    /// occurrence already is such, nothing changes. Increments which are not positive leave the
+    /// iterator unchanged.
+    ///
+    /// Fixed-length increments of up to four weeks are jumped in closed form, as are months and
+    /// years from days which exist in every month the iteration can reach. Otherwise the iterator
+    /// steps one by one until it can jump. For days like the 31st, this is at most a few years
+    /// worth of steps.
+    pub fn seek(&mut self, moment: &NaiveDateTime) -> Result<()> {
+        let amount = self.increment.clone().calculate()?;
+        if !amount.is_a_amount() || self.amount_seconds(&amount)? <= 0 {
+            return Ok(());
+        }
+
+        if let Some(anchor) = self.anchor {
+            return self.seek_anchored(anchor, moment, &amount);
+        }
+
+        if self.had_first {
+            self.step()?;
+            self.had_first = false;
+        }
+
+        loop {
+            let current = *self.base.get_moment().expect("Iter base is always a moment");
+            if self.is_past(&current, moment) {
+                return Ok(());
+            }
+
+            match self.jump(current, moment, &amount)? {
+                Some(target) => {
+                    self.base = TimeType::moment(target);
+                    return Ok(());
+                },
+                None => self.step()?,
+            }
+        }
+    }
+
+    /// Seek to the first occurrence after `moment` and yield it, see `Iter::seek()`
+    pub fn next_after(&mut self, moment: &NaiveDateTime) -> Option<Result<TimeType>> {
+        match self.seek(moment) {
+            Ok(()) => self.next(),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn seek_anchored(&mut self, anchor: NaiveDateTime, moment: &NaiveDateTime, amount: &TimeType) -> Result<()> {
+        let direction = if self.backwards { -1 } else { 1 };
+        let shift = |index: i64, by: i64| index.checked_add(by).ok_or_else(|| self.overflow(amount));
+        let nth = |index: i64| nth_from_anchor(anchor, amount, index);
+        let next = if self.had_first {
+            shift(self.index, direction)?
+        } else {
+            self.index
+        };
+
+        // Months and years count as 30 and 360 days here, so the estimate is off by a few steps
+        let estimate = (*moment - anchor).num_seconds() / self.amount_seconds(amount)?;
+        let mut index = if self.backwards {
+            estimate.min(next)
+        } else {
+            estimate.max(next)
+        };
+
+        while index != next && self.is_past(&nth(shift(index, -direction)?)?, moment) {
+            index = shift(index, -direction)?;
+        }
+        while !self.is_past(&nth(index)?, moment) {
+            index = shift(index, direction)?;
+        }
+
+        self.had_first = false;
+        self.move_to(anchor, index)
+    }
+
+    fn move_to(&mut self, anchor: NaiveDateTime, index: i64) -> Result<()> {
+        let amount = self.increment.clone().calculate()?;
+        self.base = TimeType::moment(nth_from_anchor(anchor, &amount, index)?);
+        self.index = index;
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<()> {
+        if self.backwards {
+            self.rollback()
+        } else {
+            self.skip()
+        }
+    }
+
+    // The length of `amount` in seconds, see `TimeType::get_seconds()`, failing on overflow
+    fn amount_seconds(&self, amount: &TimeType) -> Result<i64> {
+        amount_seconds(amount).ok_or_else(|| self.overflow(amount))
+    }
+
+    fn overflow(&self, amount: &TimeType) -> Error {
+        let operation = if self.backwards {
+            Operation::Subtract
+        } else {
+            Operation::Add
+        };
+        Error::Overflow(operation, self.base.clone(), amount.clone())
+    }
+
+    fn is_past(&self, current: &NaiveDateTime, moment: &NaiveDateTime) -> bool {
+        if self.backwards {
+            current < moment
+        } else {
+            current > moment
+        }
+    }
+
+    // The first occurrence past `moment` in closed form, `None` if it cannot be calculated that way
+    fn jump(&self, current: NaiveDateTime, moment: &NaiveDateTime, amount: &TimeType) -> Result<Option<NaiveDateTime>> {
+        let apply = |amount: TimeType| -> Result<NaiveDateTime> {
+            let tt = if self.backwards {
+                TimeType::moment(current) - amount
+            } else {
+                TimeType::moment(current) + amount
+            };
+
+            match tt.calculate()? {
+                TimeType::Moment(m) => Ok(m),
+                other => Err(Error::NotAMoment(other)),
+            }
+        };
+
+        // Find the smallest number of steps getting past `moment`, starting from an estimate
+        let search = |estimate: i64, nth: &dyn Fn(i64) -> Result<NaiveDateTime>| -> Result<NaiveDateTime> {
+            let mut k = estimate.max(1);
+            while k > 1 && self.is_past(&nth(k - 1)?, moment) {
+                k -= 1;
+            }
+            loop {
+                let candidate = nth(k)?;
+                if self.is_past(&candidate, moment) {
+                    return Ok(candidate);
+                }
+                k += 1;
+            }
+        };
+
+        let (earlier, later) = if self.backwards {
+            (moment, &current)
+        } else {
+            (&current, moment)
+        };
+        let months_between =
+            i64::from(later.year() - earlier.year()) * 12 + i64::from(later.month()) - i64::from(earlier.month());
+
+        let overflow = || self.overflow(amount);
+        let times = |k: i64, n: i64| k.checked_mul(n).ok_or_else(overflow);
+
+        match *amount {
+            TimeType::Years(n) => {
+                if current.month() == 2 && current.day() == 29 {
+                    return Ok(None);
+                }
+                search(months_between / 12 / n, &|k| apply(TimeType::years(times(k, n)?))).map(Some)
+            },
+            TimeType::Months(n) => {
+                let n = if self.backwards { -n } else { n };
+                if !months_regular(current.month(), current.day(), n) {
+                    return Ok(None);
+                }
+                let n = n.abs();
+                search(months_between / n, &|k| apply(TimeType::months(times(k, n)?))).map(Some)
+            },
+            _ => {
+                let seconds = self.amount_seconds(amount)?;
+                if !self.backwards && seconds > MAX_JUMPABLE_SECONDS {
+                    return Ok(None);
+                }
+
+                // Steps drop the sub-second part of the start
+                let start = current.with_nanosecond(0).unwrap_or(current);
+                let ahead = if self.backwards {
+                    start - *moment
+                } else {
+                    *moment - start
+                };
+                let k = ahead.num_seconds().max(0).checked_add(seconds).ok_or_else(overflow)? / seconds;
+
+                let delta = Duration::try_seconds(times(k, seconds)?).ok_or_else(overflow)?;
+                let target = if self.backwards {
+                    start.checked_sub_signed(delta)
+                } else {
+                    start.checked_add_signed(delta)
+                };
+                target.ok_or_else(overflow).map(Some)
+            },
+        }
+    }
+
     fn recalculate(&mut self) -> Result<()> {
         self.base.clone().calculate().map(|res| {
             self.base = res;
@@ -76,9 +346,61 @@ impl Iterator for Iter {
             self.had_first = true;
             Some(Ok(self.base.clone()))
         } else {
-            Some(self.skip().map(|_| self.base.clone()))
+            let step = if self.backwards { self.rollback() } else { self.skip() };
+            Some(step.map(|_| self.base.clone()))
+        }
+    }
+}
+
+// `TimeType::get_seconds()`, but None on overflow
+fn amount_seconds(amount: &TimeType) -> Option<i64> {
+    let (n, factor) = match *amount {
+        TimeType::Seconds(n) => (n, 1),
+        TimeType::Minutes(n) => (n, 60),
+        TimeType::Hours(n) => (n, 60 * 60),
+        TimeType::Days(n) => (n, 60 * 60 * 24),
+        TimeType::Months(n) => (n, 60 * 60 * 24 * 30),
+        TimeType::Years(n) => (n, 60 * 60 * 24 * 30 * 12),
+        _ => (0, 0),
+    };
+    n.checked_mul(factor)
+}
+
+// The occurrence `index` steps of `amount` away from `anchor`
+fn nth_from_anchor(anchor: NaiveDateTime, amount: &TimeType, index: i64) -> Result<NaiveDateTime> {
+    let overflow = || Error::Overflow(Operation::Add, TimeType::moment(anchor), amount.clone());
+
+    let months = match *amount {
+        TimeType::Months(n) => Some(n),
+        TimeType::Years(n) => n.checked_mul(12),
+        _ => None,
+    };
+
+    match months {
+        Some(months) => months
+            .checked_mul(index)
+            .and_then(|months| add_months_clamped(&anchor, months)),
+        None => amount_seconds(amount)
+            .and_then(|seconds| seconds.checked_mul(index))
+            .and_then(Duration::try_seconds)
+            .and_then(|delta| anchor.checked_add_signed(delta)),
+    }
+    .ok_or_else(overflow)
+}
+
+// Whether `day` exists in every month reached by stepping `months` at a time from `month`
+//
+// February counts 28 days, as the steps can reach years which are no leap years.
+fn months_regular(month: u32, day: u32, months: i64) -> bool {
+    let step = months.rem_euclid(12);
+    let mut month = i64::from(month);
+    for _ in 0..12 {
+        month = (month - 1 + step) % 12 + 1;
+        if i64::from(day) > get_num_of_days_in_month(2001, month) {
+            return false;
         }
     }
+    true
 }
 
 #[derive(Debug)]
@@ -185,17 +507,104 @@ where
     }
 }
 
-#[derive(Debug)]
-pub struct UntilIter<I>(I, NaiveDateTime)
+// An ending which is not resolved yet
+type Ending = Box<dyn FnOnce(&NaiveDateTime) -> Result<NaiveDateTime>>;
+
+/// The iterator returned by `Until::until()`
+///
+/// The ending is resolved from the first moment of the iteration, see `IntoMoment`, so `bound()`
+/// is `None` until the first occurrence was yielded.
+pub struct UntilIter<I>
 where
-    I: Iterator<Item = Result<TimeType>>;
+    I: Iterator<Item = Result<TimeType>>,
+{
+    inner: I,
+    peeked: Option<Result<TimeType>>,
+    ending: Option<Ending>,
+    bound: fn(NaiveDateTime) -> Bound,
+    end: Option<Bound>,
+}
 
 impl<I> UntilIter<I>
 where
     I: Iterator<Item = Result<TimeType>>,
 {
-    fn new(i: I, date: NaiveDateTime) -> UntilIter<I> {
-        UntilIter(i, date)
+    fn new<E: IntoMoment + 'static>(i: I, ending: E) -> UntilIter<I> {
+        UntilIter {
+            inner: i,
+            peeked: None,
+            ending: Some(Box::new(move |from| ending.into_moment(from))),
+            bound: Bound::Exclusive,
+            end: None,
+        }
+    }
+
+    /// Include occurrences at the very moment of the ending, which are left out by default
+    pub fn inclusive(mut self) -> UntilIter<I> {
+        self.bound = Bound::Inclusive;
+        self.end = self.end.map(|end| Bound::Inclusive(*end.moment()));
+        self
+    }
+
+    pub fn bound(&self) -> Option<&Bound> {
+        self.end.as_ref()
+    }
+
+    /// Turn the occurrences into intervals of `length`, see `WithLength`
+    ///
+    /// `overhang` decides about the last interval, if it reaches beyond the end of the iteration.
+    /// With `Overhang::Clip`, an occurrence at the moment of an inclusive ending has nothing left
+    /// and ends the iteration. Clipping needs the ending, so the first occurrence is calculated
+    /// right away.
+    pub fn intervals<L>(mut self, length: L, overhang: Overhang) -> IntervalIter<Self>
+    where
+        L: Into<Length>,
+    {
+        if overhang == Overhang::Clip && self.ending.is_some() {
+            self.peeked = self.next_calculated();
+        }
+
+        let end = self.end.as_ref().map(|end| *end.moment());
+        let intervals = IntervalIter::new(self, length.into());
+        match (overhang, end) {
+            (Overhang::Clip, Some(end)) => intervals.clipped(end),
+            _ => intervals,
+        }
+    }
+
+    // The next calculated moment of the inner iterator, resolving the ending from the first one
+    fn next_calculated(&mut self) -> Option<Result<TimeType>> {
+        if let Some(peeked) = self.peeked.take() {
+            return Some(peeked);
+        }
+
+        let tt = match self.inner.next()?.and_then(TimeType::calculate) {
+            Ok(tt) if tt.is_moment() => tt,
+            Ok(tt) => return Some(Err(Error::NotAMoment(tt))),
+            Err(e) => return Some(Err(e)),
+        };
+
+        if let Some(ending) = self.ending.take() {
+            match ending(tt.get_moment().unwrap()) {
+                Ok(end) => self.end = Some((self.bound)(end)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        Some(Ok(tt))
+    }
+}
+
+impl<I> fmt::Debug for UntilIter<I>
+where
+    I: Iterator<Item = Result<TimeType>> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UntilIter")
+            .field("inner", &self.inner)
+            .field("peeked", &self.peeked)
+            .field("end", &self.end)
+            .finish()
     }
 }
 
@@ -206,40 +615,278 @@ where
     type Item = Result<TimeType>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.0.next() {
-            None => None,
-            Some(Err(e)) => Some(Err(e)),
-            Some(Ok(tt)) => match tt.calculate() {
-                Err(e) => Some(Err(e)),
-                Ok(tt) => {
-                    if tt.is_moment() {
-                        if tt.get_moment().unwrap() < &self.1 {
-                            Some(Ok(tt))
-                        } else {
-                            None
-                        }
-                    } else {
-                        Some(Err(Error::ArgumentErrorNotAMoment(tt.name())))
-                    }
-                },
+        match self.next_calculated()? {
+            Ok(tt) if self.end?.is_above(tt.get_moment().unwrap()) => Some(Ok(tt)),
+            Ok(_) => {
+                self.end = None;
+                None
             },
+            Err(e) => Some(Err(e)),
         }
     }
 }
 
+/// Helper trait for passing moments, `TimeType` expressions and marks as the end of an iteration
+///
+/// Expressions must calculate to a moment. Marks are resolved relative to `from`, see
+/// `Mark::resolve()`, which is the first moment of the iteration for both `Occurrences::until()`
+/// and `Until::until()`.
+pub trait IntoMoment {
+    fn into_moment(self, from: &NaiveDateTime) -> Result<NaiveDateTime>;
+}
+
+impl IntoMoment for NaiveDateTime {
+    fn into_moment(self, _: &NaiveDateTime) -> Result<NaiveDateTime> {
+        Ok(self)
+    }
+}
+
+impl IntoMoment for TimeType {
+    fn into_moment(self, _: &NaiveDateTime) -> Result<NaiveDateTime> {
+        calculated_moment(self).map(|(moment, _)| moment)
+    }
+}
+
+impl IntoMoment for Mark {
+    fn into_moment(self, from: &NaiveDateTime) -> Result<NaiveDateTime> {
+        self.resolve(from)
+    }
+}
+
+/// End the iteration with the first moment at or after `ending`
+///
+/// The ending is exclusive, use `UntilIter::inclusive()` to include it. If `ending` is an
+/// expression which does not calculate to a moment, the error is yielded once and the iteration
+/// ends.
 pub trait Until: Iterator<Item = Result<TimeType>> + Sized {
-    fn until(self, ending: NaiveDateTime) -> UntilIter<Self>;
+    fn until<E: IntoMoment + 'static>(self, ending: E) -> UntilIter<Self>;
 }
 
 impl<I> Until for I
 where
     I: Iterator<Item = Result<TimeType>>,
 {
-    fn until(self, ending: NaiveDateTime) -> UntilIter<Self> {
+    fn until<E: IntoMoment + 'static>(self, ending: E) -> UntilIter<Self> {
         UntilIter::new(self, ending)
     }
 }
 
+/// The iterator returned by `Since::since()`
+///
+/// Like the ending of `UntilIter`, the beginning is resolved from the first moment of the
+/// iteration, so `bound()` is `None` until the first occurrence was yielded.
+pub struct SinceIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    inner: I,
+    beginning: Option<Ending>,
+    bound: fn(NaiveDateTime) -> Bound,
+    start: Option<Bound>,
+}
+
+impl<I> SinceIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn new<E: IntoMoment + 'static>(i: I, beginning: E) -> SinceIter<I> {
+        SinceIter {
+            inner: i,
+            beginning: Some(Box::new(move |from| beginning.into_moment(from))),
+            bound: Bound::Inclusive,
+            start: None,
+        }
+    }
+
+    /// Leave out occurrences at the very moment of the beginning, which are included by default
+    pub fn exclusive(mut self) -> SinceIter<I> {
+        self.bound = Bound::Exclusive;
+        self.start = self.start.map(|start| Bound::Exclusive(*start.moment()));
+        self
+    }
+
+    pub fn bound(&self) -> Option<&Bound> {
+        self.start.as_ref()
+    }
+}
+
+impl<I> fmt::Debug for SinceIter<I>
+where
+    I: Iterator<Item = Result<TimeType>> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SinceIter")
+            .field("inner", &self.inner)
+            .field("start", &self.start)
+            .finish()
+    }
+}
+
+impl<I> Iterator for SinceIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (moment, tt) = match self.inner.next()?.and_then(calculated_moment) {
+            Ok(calculated) => calculated,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if let Some(beginning) = self.beginning.take() {
+            match beginning(&moment) {
+                Ok(start) => self.start = Some((self.bound)(start)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        if self.start?.is_below(&moment) {
+            Some(Ok(tt))
+        } else {
+            self.start = None;
+            None
+        }
+    }
+}
+
+/// The lower bound counterpart of `Until`, for iterating backwards
+///
+/// Ends the iteration with the first moment before `beginning`. The beginning is inclusive, use
+/// `SinceIter::exclusive()` to leave it out. It is passed like the ending of `Until::until()`,
+/// see `IntoMoment`.
+pub trait Since: Iterator<Item = Result<TimeType>> + Sized {
+    fn since<E: IntoMoment + 'static>(self, beginning: E) -> SinceIter<Self>;
+}
+
+impl<I> Since for I
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn since<E: IntoMoment + 'static>(self, beginning: E) -> SinceIter<Self> {
+        SinceIter::new(self, beginning)
+    }
+}
+
+/// How long each occurrence lasts, see `WithLength`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Length {
+    /// A `TimeType` amount
+    Amount(TimeType),
+
+    /// Until the following midnight
+    EndOfDay,
+
+    /// Until midnight of the first day of the following month
+    EndOfMonth,
+}
+
+impl From<TimeType> for Length {
+    fn from(amount: TimeType) -> Length {
+        Length::Amount(amount)
+    }
+}
+
+impl Length {
+    fn interval_from(&self, start: NaiveDateTime) -> Result<Interval> {
+        let overflow = |amount| Error::Overflow(Operation::Add, TimeType::moment(start), amount);
+
+        match *self {
+            Length::Amount(ref amount) => Interval::from_start_and_length(TimeType::moment(start), amount.clone()),
+            Length::EndOfDay => {
+                let end = start.date().succ_opt().ok_or_else(|| overflow(TimeType::days(1)))?;
+                Interval::new(start, end.and_time(NaiveTime::MIN))
+            },
+            Length::EndOfMonth => {
+                let first = start.date().with_day(1).expect("Every month has a first day");
+                let end = add_months_clamped(&first.and_time(NaiveTime::MIN), 1)
+                    .ok_or_else(|| overflow(TimeType::months(1)))?;
+                Interval::new(start, end)
+            },
+        }
+    }
+}
+
+/// What happens to an interval reaching beyond the end of the iteration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overhang {
+    /// Yield the interval as it is
+    Include,
+
+    /// Cut the interval at the end of the iteration
+    Clip,
+}
+
+#[derive(Debug)]
+pub struct IntervalIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    inner: I,
+    length: Length,
+    end: Option<NaiveDateTime>,
+}
+
+impl<I> IntervalIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn new(inner: I, length: Length) -> IntervalIter<I> {
+        IntervalIter {
+            inner,
+            length,
+            end: None,
+        }
+    }
+
+    /// End the iteration at `end`, cutting the last interval if it reaches beyond
+    pub fn clipped(mut self, end: NaiveDateTime) -> IntervalIter<I> {
+        self.end = Some(end);
+        self
+    }
+}
+
+impl<I> Iterator for IntervalIter<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<Interval>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, _) = match self.inner.next()?.and_then(calculated_moment) {
+            Ok(calculated) => calculated,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match self.end {
+            Some(end) if start >= end => None,
+            Some(end) => Some(
+                self.length
+                    .interval_from(start)
+                    .and_then(|interval| Interval::new(start, std::cmp::min(*interval.end(), end))),
+            ),
+            None => Some(self.length.interval_from(start)),
+        }
+    }
+}
+
+/// Attach a length to each occurrence, yielding `Interval`s from the occurrence to its end
+///
+/// Use `UntilIter::intervals()` or `IntervalIter::clipped()` to decide about intervals reaching
+/// beyond the end of a bounded iteration.
+pub trait WithLength: Iterator<Item = Result<TimeType>> + Sized {
+    fn with_length<L: Into<Length>>(self, length: L) -> IntervalIter<Self>;
+}
+
+impl<I> WithLength for I
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn with_length<L: Into<Length>>(self, length: L) -> IntervalIter<Self> {
+        IntervalIter::new(self, length.into())
+    }
+}
+
 #[derive(Debug)]
 pub struct TimesIter<I>
 where
@@ -292,27 +939,545 @@ where
     }
 }
 
-pub mod extensions {
-    use crate::error::Error;
-    use crate::error::Result;
-    use crate::timetype::TimeType as TT;
+#[derive(Debug)]
+struct MergeSource<T, I> {
+    tag: T,
+    iter: I,
+    head: Option<(NaiveDateTime, TimeType)>,
+    done: bool,
+}
 
-    use super::Iter;
+/// Merges chronological occurrence iterators into one chronological iterator
+///
+/// Each item is tagged with the source it came from. Occurrences at the same moment are yielded in
+/// the order of their sources, or only once with `dedup()`. Errors of the sources are passed
+/// through as they come up.
+///
+/// Iterators of different types, like `Iter`, `FilterIter` and `UntilIter`, can be merged by
+/// boxing them as `Box<dyn Iterator<Item = Result<TimeType>>>`.
+#[derive(Debug)]
+pub struct MergeIter<T, I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    sources: Vec<MergeSource<T, I>>,
+    dedup: bool,
+    last: Option<NaiveDateTime>,
+}
 
-    pub trait Minutely {
-        fn minutely(self, i: i64) -> Result<Iter>;
+impl<I> MergeIter<usize, I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    /// Merge `sources`, tagging each item with the index of its source
+    pub fn new<S>(sources: S) -> MergeIter<usize, I>
+    where
+        S: IntoIterator<Item = I>,
+    {
+        MergeIter::tagged(sources.into_iter().enumerate())
     }
+}
 
-    pub trait Hourly {
-        fn hourly(self, i: i64) -> Result<Iter>;
+impl<T, I> MergeIter<T, I>
+where
+    T: Clone,
+    I: Iterator<Item = Result<TimeType>>,
+{
+    /// Merge `sources`, tagging each item with the tag of its source
+    pub fn tagged<S>(sources: S) -> MergeIter<T, I>
+    where
+        S: IntoIterator<Item = (T, I)>,
+    {
+        let sources = sources
+            .into_iter()
+            .map(|(tag, iter)| MergeSource {
+                tag,
+                iter,
+                head: None,
+                done: false,
+            })
+            .collect();
+
+        MergeIter {
+            sources,
+            dedup: false,
+            last: None,
+        }
     }
 
-    pub trait Daily {
-        fn daily(self, i: i64) -> Result<Iter>;
+    /// Yield occurrences at the same moment only once, tagged with the first source
+    pub fn dedup(mut self) -> MergeIter<T, I> {
+        self.dedup = true;
+        self
     }
 
-    pub trait Weekly: Sized {
-        fn weekly(self, i: i64) -> Result<Iter>;
+    /// Drop the tags, so the merged occurrences can be passed to the other adapters
+    pub fn untagged(self) -> UntaggedIter<T, I> {
+        UntaggedIter(self)
+    }
+
+    // Make sure every source which is not done has a head
+    fn fill(&mut self) -> Result<()> {
+        for source in self.sources.iter_mut().filter(|s| s.head.is_none() && !s.done) {
+            match source.iter.next() {
+                None => source.done = true,
+                Some(tt) => source.head = Some(calculated_moment(tt?)?),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, I> Iterator for MergeIter<T, I>
+where
+    T: Clone,
+    I: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<(T, TimeType)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+
+            let (_, index) = self
+                .sources
+                .iter()
+                .enumerate()
+                .filter_map(|(i, source)| source.head.as_ref().map(|(m, _)| (*m, i)))
+                .min()?;
+
+            let source = &mut self.sources[index];
+            let (moment, tt) = source.head.take()?;
+            if self.dedup && self.last == Some(moment) {
+                continue;
+            }
+
+            self.last = Some(moment);
+            return Some(Ok((source.tag.clone(), tt)));
+        }
+    }
+}
+
+/// A `MergeIter` yielding the occurrences without their tags, see `MergeIter::untagged()`
+#[derive(Debug)]
+pub struct UntaggedIter<T, I>(MergeIter<T, I>)
+where
+    I: Iterator<Item = Result<TimeType>>;
+
+impl<T, I> Iterator for UntaggedIter<T, I>
+where
+    T: Clone,
+    I: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|item| item.map(|(_, tt)| tt))
+    }
+}
+
+// Calculate `tt`, which has to result in a moment
+fn calculated_moment(tt: TimeType) -> Result<(NaiveDateTime, TimeType)> {
+    let tt = tt.calculate()?;
+    match tt.get_moment() {
+        Some(m) => Ok((*m, tt)),
+        None => Err(Error::NotAMoment(tt)),
+    }
+}
+
+/// When occurrences of two iterators count as the same, see `Intersect` and `Except`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tolerance {
+    /// At the very same moment
+    SameInstant,
+
+    /// On the same day, at any time
+    SameDay,
+
+    /// At most the duration apart, in either direction
+    Within(Duration),
+}
+
+impl Tolerance {
+    // Whether `other` is too early for, matching or too late for `moment`
+    fn compare(&self, other: &NaiveDateTime, moment: &NaiveDateTime) -> Ordering {
+        match *self {
+            Tolerance::SameInstant => other.cmp(moment),
+            Tolerance::SameDay => other.date().cmp(&moment.date()),
+            Tolerance::Within(duration) => {
+                let duration = duration.abs();
+                if moment
+                    .checked_sub_signed(duration)
+                    .is_some_and(|earliest| *other < earliest)
+                {
+                    Ordering::Less
+                } else if moment
+                    .checked_add_signed(duration)
+                    .is_some_and(|latest| *other > latest)
+                {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            },
+        }
+    }
+}
+
+// The sorted iterator intersected with or subtracted from another one
+#[derive(Debug)]
+struct Lookup<I> {
+    iter: I,
+    head: Option<NaiveDateTime>,
+    done: bool,
+    tolerance: Tolerance,
+}
+
+impl<I> Lookup<I>
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn new(iter: I, tolerance: Tolerance) -> Lookup<I> {
+        Lookup {
+            iter,
+            head: None,
+            done: false,
+            tolerance,
+        }
+    }
+
+    // Whether an occurrence matches `moment`, dropping all occurrences before it
+    //
+    // The matching occurrence is kept, as it may match the next moment as well.
+    fn contains(&mut self, moment: &NaiveDateTime) -> Result<bool> {
+        loop {
+            if self.head.is_none() && !self.done {
+                match self.iter.next() {
+                    None => self.done = true,
+                    Some(tt) => self.head = Some(calculated_moment(tt?)?.0),
+                }
+            }
+
+            let head = match self.head {
+                Some(head) => head,
+                None => return Ok(false),
+            };
+
+            match self.tolerance.compare(&head, moment) {
+                Ordering::Less => self.head = None,
+                Ordering::Equal => return Ok(true),
+                Ordering::Greater => return Ok(false),
+            }
+        }
+    }
+
+    // Whether no occurrence is left, so no further moment can match
+    fn is_exhausted(&self) -> bool {
+        self.done && self.head.is_none()
+    }
+}
+
+#[derive(Debug)]
+pub struct IntersectIter<I, O>
+where
+    I: Iterator<Item = Result<TimeType>>,
+    O: Iterator<Item = Result<TimeType>>,
+{
+    inner: I,
+    other: Lookup<O>,
+}
+
+impl<I, O> Iterator for IntersectIter<I, O>
+where
+    I: Iterator<Item = Result<TimeType>>,
+    O: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // `inner` may be endless, so stop as soon as nothing can match anymore
+            if self.other.is_exhausted() {
+                return None;
+            }
+
+            let (moment, tt) = match self.inner.next()?.and_then(calculated_moment) {
+                Ok(calculated) => calculated,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.other.contains(&moment) {
+                Ok(true) => return Some(Ok(tt)),
+                Ok(false) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Keep the occurrences which also occur in `other`
+///
+/// Both iterators have to be chronological. `other` is consumed lazily, as far as needed.
+pub trait Intersect: Iterator<Item = Result<TimeType>> + Sized {
+    fn intersect<O>(self, other: O, tolerance: Tolerance) -> IntersectIter<Self, O>
+    where
+        O: Iterator<Item = Result<TimeType>>;
+}
+
+impl<I> Intersect for I
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn intersect<O>(self, other: O, tolerance: Tolerance) -> IntersectIter<Self, O>
+    where
+        O: Iterator<Item = Result<TimeType>>,
+    {
+        IntersectIter {
+            inner: self,
+            other: Lookup::new(other, tolerance),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ExceptIter<I, O>
+where
+    I: Iterator<Item = Result<TimeType>>,
+    O: Iterator<Item = Result<TimeType>>,
+{
+    inner: I,
+    other: Lookup<O>,
+}
+
+impl<I, O> Iterator for ExceptIter<I, O>
+where
+    I: Iterator<Item = Result<TimeType>>,
+    O: Iterator<Item = Result<TimeType>>,
+{
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (moment, tt) = match self.inner.next()?.and_then(calculated_moment) {
+                Ok(calculated) => calculated,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.other.contains(&moment) {
+                Ok(false) => return Some(Ok(tt)),
+                Ok(true) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Leave out the occurrences which also occur in `other`, the difference of both iterators
+///
+/// Both iterators have to be chronological. `other` is consumed lazily, as far as needed.
+pub trait Except: Iterator<Item = Result<TimeType>> + Sized {
+    fn except<O>(self, other: O, tolerance: Tolerance) -> ExceptIter<Self, O>
+    where
+        O: Iterator<Item = Result<TimeType>>;
+}
+
+impl<I> Except for I
+where
+    I: Iterator<Item = Result<TimeType>>,
+{
+    fn except<O>(self, other: O, tolerance: Tolerance) -> ExceptIter<Self, O>
+    where
+        O: Iterator<Item = Result<TimeType>>,
+    {
+        ExceptIter {
+            inner: self,
+            other: Lookup::new(other, tolerance),
+        }
+    }
+}
+
+/// What `TimeType::every()` steps by
+pub enum Increment {
+    /// Add an amount in each step
+    Amount(TimeType),
+
+    /// Go day by day, only stopping at days the matcher matches
+    Matching(Box<dyn Matcher>),
+}
+
+/// Helper trait for passing amounts as well as matchers to `TimeType::every()`
+pub trait IntoIncrement {
+    fn into_increment(self) -> Increment;
+}
+
+impl IntoIncrement for TimeType {
+    fn into_increment(self) -> Increment {
+        Increment::Amount(self)
+    }
+}
+
+impl<M> IntoIncrement for M
+where
+    M: Matcher + 'static,
+{
+    fn into_increment(self) -> Increment {
+        Increment::Matching(Box::new(self))
+    }
+}
+
+/// The iterator returned by `TimeType::every()`
+///
+/// Occurrences can be narrowed down with `skip()` and bounded with `until()` or
+/// `until_inclusive()`. These are inherent methods, so `Iterator::skip()` has to be called
+/// explicitly if it is meant.
+///
+/// If the start is not a moment or the step is not an amount, the error is yielded once and the
+/// iteration ends. The iteration ends after the first error in general.
+///
+/// # Warning
+///
+/// Without `until()` or `Iterator::take()`, the iteration is endless. If no day matches the
+/// matchers, `next()` does not return before the moments overflow.
+pub struct Occurrences {
+    inner: Option<Iter>,
+    error: Option<Error>,
+    matchers: Vec<(bool, Box<dyn Matcher>)>,
+    end: Option<Bound>,
+}
+
+impl Occurrences {
+    pub(crate) fn new(start: TimeType, step: Increment) -> Occurrences {
+        let (increment, matcher) = match step {
+            Increment::Amount(amount) => (amount, None),
+            Increment::Matching(m) => (TimeType::days(1), Some(m)),
+        };
+
+        let inner = start.calculate().and_then(|start| match start {
+            TimeType::Moment(m) => Iter::build(m, increment),
+            other => Err(Error::NotAMoment(other)),
+        });
+
+        let mut occurrences = Occurrences {
+            inner: None,
+            error: None,
+            matchers: matcher.into_iter().map(|m| (true, m)).collect(),
+            end: None,
+        };
+
+        match inner {
+            Ok(iter) => occurrences.inner = Some(iter),
+            Err(e) => occurrences.error = Some(e),
+        }
+
+        occurrences
+    }
+
+    /// Leave out all occurrences `matcher` matches
+    ///
+    /// Besides matchers, closures on moments or `TimeType`s can be passed, see
+    /// `matcher::IntoMatcher`.
+    pub fn skip<K, M>(mut self, matcher: M) -> Occurrences
+    where
+        M: IntoMatcher<K>,
+    {
+        self.matchers.push((false, matcher.into_matcher()));
+        self
+    }
+
+    /// End the iteration before `ending`
+    ///
+    /// The ending can be a moment, a `TimeType` expression or a `Mark`, which is resolved relative
+    /// to the start of the iteration, see `IntoMoment`.
+    pub fn until<E: IntoMoment>(self, ending: E) -> Occurrences {
+        self.bounded(ending, Bound::Exclusive)
+    }
+
+    /// End the iteration after `ending`, including occurrences at the very moment of it
+    pub fn until_inclusive<E: IntoMoment>(self, ending: E) -> Occurrences {
+        self.bounded(ending, Bound::Inclusive)
+    }
+
+    fn bounded<E: IntoMoment>(mut self, ending: E, bound: fn(NaiveDateTime) -> Bound) -> Occurrences {
+        if let Some(iter) = self.inner.as_ref() {
+            let start = *iter.base.get_moment().expect("Iter base is always a moment");
+            match ending.into_moment(&start) {
+                Ok(end) => self.end = Some(bound(end)),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        self
+    }
+
+    fn accepts(&self, tt: &TimeType) -> Result<bool> {
+        for (expected, matcher) in self.matchers.iter() {
+            if matcher.matches(tt)? != *expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Iterator for Occurrences {
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.inner = None;
+            return Some(Err(e));
+        }
+
+        loop {
+            let tt = match self.inner.as_mut()?.next()? {
+                Ok(tt) => tt,
+                Err(e) => {
+                    self.inner = None;
+                    return Some(Err(e));
+                },
+            };
+
+            if let (Some(end), Some(m)) = (self.end.as_ref(), tt.get_moment()) {
+                if !end.is_above(m) {
+                    self.inner = None;
+                    return None;
+                }
+            }
+
+            match self.accepts(&tt) {
+                Ok(true) => return Some(Ok(tt)),
+                Ok(false) => continue,
+                Err(e) => {
+                    self.inner = None;
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
+}
+
+pub mod extensions {
+    use crate::error::Error;
+    use crate::error::Result;
+    use crate::timetype::TimeType as TT;
+
+    use super::Iter;
+
+    pub trait Minutely {
+        fn minutely(self, i: i64) -> Result<Iter>;
+    }
+
+    pub trait Hourly {
+        fn hourly(self, i: i64) -> Result<Iter>;
+    }
+
+    pub trait Daily {
+        fn daily(self, i: i64) -> Result<Iter>;
+    }
+
+    pub trait Weekly: Sized {
+        fn weekly(self, i: i64) -> Result<Iter>;
     }
 
     pub trait Monthly {
@@ -323,6 +1488,8 @@ pub mod extensions {
         fn yearly(self, i: i64) -> Result<Iter>;
     }
 
+    /// Superseded by the inherent `TimeType::every()`, which takes precedence with method syntax
+    #[deprecated(note = "use TimeType::every(), which returns the Occurrences iterator directly")]
     pub trait Every {
         fn every(self, inc: TT) -> Result<Iter>;
     }
@@ -335,7 +1502,7 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
@@ -348,7 +1515,7 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
@@ -361,7 +1528,7 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
@@ -375,7 +1542,7 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
@@ -388,7 +1555,7 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
@@ -401,105 +1568,100 @@ pub mod extensions {
                     assert!(increment.is_a_amount(), "This is a Bug, please report this!");
                     Iter::build(mom, increment)
                 },
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
 
+    #[allow(deprecated)]
     impl Every for TT {
         fn every(self, inc: TT) -> Result<Iter> {
             match self {
                 TT::Moment(mom) => Iter::build(mom, inc),
-                _ => Err(Error::ArgumentErrorNotAnAmount(self)),
+                _ => Err(Error::NotAnAmount(self)),
             }
         }
     }
 
     #[cfg(test)]
     mod tests {
-        use chrono::NaiveDate as ND;
-
-        use crate::timetype::TimeType as TT;
+        use crate::util::tests::moment_hms;
 
         use super::*;
 
-        fn ymd_hms(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> TT {
-            TT::moment(ND::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, mi, s).unwrap())
-        }
-
         #[test]
         fn test_minutely() {
-            let minutes = ymd_hms(2000, 1, 1, 0, 0, 0)
+            let minutes = moment_hms(2000, 1, 1, 0, 0, 0)
                 .minutely(1)
                 .unwrap()
                 .take(5)
                 .collect::<Vec<_>>();
 
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 1, 0), *minutes[1].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 2, 0), *minutes[2].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 3, 0), *minutes[3].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 4, 0), *minutes[4].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 1, 0), *minutes[1].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 2, 0), *minutes[2].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 3, 0), *minutes[3].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 4, 0), *minutes[4].as_ref().unwrap());
         }
 
         #[test]
         fn test_hourly() {
-            let minutes = ymd_hms(2000, 1, 1, 0, 0, 0)
+            let minutes = moment_hms(2000, 1, 1, 0, 0, 0)
                 .hourly(1)
                 .unwrap()
                 .take(5)
                 .collect::<Vec<_>>();
 
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 1, 0, 0), *minutes[1].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 2, 0, 0), *minutes[2].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 3, 0, 0), *minutes[3].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 1, 4, 0, 0), *minutes[4].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 1, 0, 0), *minutes[1].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 2, 0, 0), *minutes[2].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 3, 0, 0), *minutes[3].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 4, 0, 0), *minutes[4].as_ref().unwrap());
         }
 
         #[test]
         fn test_weekly() {
-            let minutes = ymd_hms(2000, 1, 1, 1, 0, 0)
+            let minutes = moment_hms(2000, 1, 1, 1, 0, 0)
                 .weekly(1)
                 .unwrap()
                 .take(5)
                 .collect::<Vec<_>>();
 
-            assert_eq!(ymd_hms(2000, 1, 1, 1, 0, 0), *minutes[0].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 8, 1, 0, 0), *minutes[1].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 15, 1, 0, 0), *minutes[2].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 22, 1, 0, 0), *minutes[3].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 1, 29, 1, 0, 0), *minutes[4].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 1, 0, 0), *minutes[0].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 8, 1, 0, 0), *minutes[1].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 15, 1, 0, 0), *minutes[2].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 22, 1, 0, 0), *minutes[3].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 29, 1, 0, 0), *minutes[4].as_ref().unwrap());
         }
 
         #[test]
         fn test_monthly() {
-            let minutes = ymd_hms(2000, 1, 1, 0, 0, 0)
+            let minutes = moment_hms(2000, 1, 1, 0, 0, 0)
                 .monthly(1)
                 .unwrap()
                 .take(5)
                 .collect::<Vec<_>>();
 
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 2, 1, 0, 0, 0), *minutes[1].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 3, 1, 0, 0, 0), *minutes[2].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 4, 1, 0, 0, 0), *minutes[3].as_ref().unwrap());
-            assert_eq!(ymd_hms(2000, 5, 1, 0, 0, 0), *minutes[4].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 2, 1, 0, 0, 0), *minutes[1].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 3, 1, 0, 0, 0), *minutes[2].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 4, 1, 0, 0, 0), *minutes[3].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 5, 1, 0, 0, 0), *minutes[4].as_ref().unwrap());
         }
 
         #[test]
         fn test_yearly() {
-            let minutes = ymd_hms(2000, 1, 1, 0, 0, 0)
+            let minutes = moment_hms(2000, 1, 1, 0, 0, 0)
                 .yearly(1)
                 .unwrap()
                 .take(5)
                 .collect::<Vec<_>>();
 
-            assert_eq!(ymd_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
-            assert_eq!(ymd_hms(2001, 1, 1, 0, 0, 0), *minutes[1].as_ref().unwrap());
-            assert_eq!(ymd_hms(2002, 1, 1, 0, 0, 0), *minutes[2].as_ref().unwrap());
-            assert_eq!(ymd_hms(2003, 1, 1, 0, 0, 0), *minutes[3].as_ref().unwrap());
-            assert_eq!(ymd_hms(2004, 1, 1, 0, 0, 0), *minutes[4].as_ref().unwrap());
+            assert_eq!(moment_hms(2000, 1, 1, 0, 0, 0), *minutes[0].as_ref().unwrap());
+            assert_eq!(moment_hms(2001, 1, 1, 0, 0, 0), *minutes[1].as_ref().unwrap());
+            assert_eq!(moment_hms(2002, 1, 1, 0, 0, 0), *minutes[2].as_ref().unwrap());
+            assert_eq!(moment_hms(2003, 1, 1, 0, 0, 0), *minutes[3].as_ref().unwrap());
+            assert_eq!(moment_hms(2004, 1, 1, 0, 0, 0), *minutes[4].as_ref().unwrap());
         }
     }
 }
@@ -611,3 +1773,1059 @@ mod test_until {
         assert_eq!(v.len(), 48);
     }
 }
+
+#[cfg(test)]
+mod test_backwards {
+    use crate::util::tests::ymd;
+
+    use super::extensions::*;
+    use super::*;
+
+    fn moments<I: Iterator<Item = Result<TimeType>>>(iter: I) -> Vec<NaiveDateTime> {
+        iter.map(|tt| *tt.unwrap().get_moment().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_backwards_daily() {
+        let iter = TimeType::moment(ymd(2024, 3, 2)).daily(1).unwrap().backwards();
+        assert!(iter.is_backwards());
+
+        let v = moments(iter.take(3));
+        assert_eq!(v, vec![ymd(2024, 3, 2), ymd(2024, 3, 1), ymd(2024, 2, 29)]);
+    }
+
+    #[test]
+    fn test_last_twelve_months_before_today() {
+        let today = ymd(2024, 3, 15);
+        let v = moments(
+            Iter::build(today, TimeType::months(1))
+                .unwrap()
+                .backwards()
+                .skip(1)
+                .times(12),
+        );
+
+        assert_eq!(v.len(), 12);
+        assert_eq!(v.first(), Some(&ymd(2024, 2, 15)));
+        assert_eq!(v.last(), Some(&ymd(2023, 3, 15)));
+    }
+
+    #[test]
+    fn test_backwards_monthly_from_month_end() {
+        let v = moments(
+            Iter::build(ymd(2024, 3, 31), TimeType::months(1))
+                .unwrap()
+                .backwards()
+                .take(5),
+        );
+        assert_eq!(
+            v,
+            vec![
+                ymd(2024, 3, 31),
+                ymd(2024, 2, 29),
+                ymd(2024, 1, 31),
+                ymd(2023, 12, 31),
+                ymd(2023, 11, 30)
+            ]
+        );
+
+        // anchoring explicitly does not change anything
+        let v = moments(
+            Iter::build(ymd(2024, 3, 31), TimeType::months(1))
+                .unwrap()
+                .backwards()
+                .anchored()
+                .take(3),
+        );
+        assert_eq!(v, vec![ymd(2024, 3, 31), ymd(2024, 2, 29), ymd(2024, 1, 31)]);
+    }
+
+    #[test]
+    fn test_backwards_yearly_from_leap_day() {
+        let v = moments(
+            Iter::build(ymd(2024, 2, 29), TimeType::years(1))
+                .unwrap()
+                .backwards()
+                .take(2),
+        );
+        assert_eq!(v, vec![ymd(2024, 2, 29), ymd(2023, 2, 28)]);
+
+        let v = moments(
+            Iter::build(ymd(2024, 2, 29), TimeType::months(12))
+                .unwrap()
+                .backwards()
+                .take(2),
+        );
+        assert_eq!(v, vec![ymd(2024, 2, 29), ymd(2023, 2, 28)]);
+    }
+
+    #[test]
+    fn test_since() {
+        let v = moments(
+            TimeType::moment(ymd(2024, 1, 10))
+                .daily(3)
+                .unwrap()
+                .backwards()
+                .since(ymd(2024, 1, 1)),
+        );
+
+        assert_eq!(
+            v,
+            vec![ymd(2024, 1, 10), ymd(2024, 1, 7), ymd(2024, 1, 4), ymd(2024, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_since_exclusive_expression() {
+        let iter = || TimeType::moment(ymd(2024, 1, 10)).daily(3).unwrap().backwards();
+        let beginning = || TimeType::moment(ymd(2024, 1, 10)) - TimeType::days(9);
+
+        assert_eq!(moments(iter().since(beginning())).len(), 4);
+        assert_eq!(
+            moments(iter().since(beginning()).exclusive()),
+            vec![ymd(2024, 1, 10), ymd(2024, 1, 7), ymd(2024, 1, 4)]
+        );
+
+        let mut since = iter().since(TimeType::days(1));
+        assert_eq!(since.next(), Some(Err(Error::NotAMoment(TimeType::days(1)))));
+        assert_eq!(since.next(), None);
+    }
+
+    #[test]
+    fn test_since_forwards_ends_immediately() {
+        let v = moments(
+            TimeType::moment(ymd(2024, 1, 10))
+                .daily(1)
+                .unwrap()
+                .since(ymd(2024, 1, 11)),
+        );
+
+        assert!(v.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test_seek {
+    use chrono::NaiveDate;
+
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    // The first occurrence past `moment`, found by stepping
+    fn brute_force(iter: Iter, moment: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let backwards = iter.is_backwards();
+        for tt in iter {
+            let m = *tt.ok()?.get_moment().unwrap();
+            if (backwards && m < *moment) || (!backwards && m > *moment) {
+                return Some(m);
+            }
+        }
+        None
+    }
+
+    fn seeked(mut iter: Iter, moment: &NaiveDateTime) -> NaiveDateTime {
+        *iter.next_after(moment).unwrap().unwrap().get_moment().unwrap()
+    }
+
+    #[test]
+    fn test_next_run() {
+        let mut iter = Iter::build(ymd_hms(2015, 1, 1, 0, 0, 0), TimeType::minutes(15)).unwrap();
+        let now = ymd_hms(2024, 6, 15, 10, 7, 30);
+
+        let next = iter.next_after(&now).unwrap().unwrap();
+        assert_eq!(next.get_moment(), Some(&ymd_hms(2024, 6, 15, 10, 15, 0)));
+
+        let after = iter.next().unwrap().unwrap();
+        assert_eq!(after.get_moment(), Some(&ymd_hms(2024, 6, 15, 10, 30, 0)));
+    }
+
+    #[test]
+    fn test_seek_does_not_go_back() {
+        let mut iter = Iter::build(ymd_hms(2024, 1, 1, 0, 0, 0), TimeType::days(1)).unwrap();
+        iter.seek(&ymd_hms(2023, 1, 1, 0, 0, 0)).unwrap();
+        assert_eq!(
+            iter.next().unwrap().unwrap().get_moment(),
+            Some(&ymd_hms(2024, 1, 1, 0, 0, 0))
+        );
+
+        iter.seek(&ymd_hms(2023, 1, 1, 0, 0, 0)).unwrap();
+        assert_eq!(
+            iter.next().unwrap().unwrap().get_moment(),
+            Some(&ymd_hms(2024, 1, 2, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_seek_matches_stepping() {
+        let increments = [
+            TimeType::seconds(7),
+            TimeType::minutes(15),
+            TimeType::hours(5),
+            TimeType::days(1),
+            TimeType::weeks(1),
+            TimeType::days(40),
+            TimeType::months(1),
+            TimeType::months(5),
+            TimeType::months(12),
+            TimeType::years(1),
+            TimeType::years(4),
+        ];
+        let starts = [
+            ymd_hms(2000, 1, 31, 0, 0, 0),
+            ymd_hms(2000, 2, 29, 12, 0, 0),
+            ymd_hms(2000, 10, 31, 23, 59, 59),
+            ymd_hms(2001, 1, 30, 8, 0, 0),
+            ymd_hms(2001, 12, 15, 6, 30, 0),
+            NaiveDate::from_ymd_opt(2001, 3, 4)
+                .unwrap()
+                .and_hms_milli_opt(1, 2, 3, 500)
+                .unwrap(),
+        ];
+        let moments = [
+            ymd_hms(1999, 6, 1, 0, 0, 0),
+            ymd_hms(2000, 2, 1, 0, 0, 0),
+            ymd_hms(2001, 3, 4, 1, 2, 3),
+            ymd_hms(2001, 3, 4, 2, 0, 0),
+            ymd_hms(2002, 1, 1, 0, 0, 0),
+            ymd_hms(2003, 2, 28, 13, 14, 15),
+        ];
+
+        for increment in increments.iter() {
+            for start in starts.iter() {
+                for moment in moments.iter() {
+                    // Keep the stepping cheap
+                    if (*moment - *start).num_seconds() / increment.get_seconds() > 10_000 {
+                        continue;
+                    }
+
+                    let iter = || Iter::build(*start, increment.clone()).unwrap();
+                    if let Some(expected) = brute_force(iter(), moment) {
+                        let found = seeked(iter(), moment);
+                        assert_eq!(found, expected, "{:?} from {} after {}", increment, start, moment);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_seek_huge_increment_overflows() {
+        let start = ymd_hms(2024, 1, 1, 0, 0, 0);
+        let amount = TimeType::days(i64::MAX / 1000);
+
+        let mut iter = Iter::build(start, amount.clone()).unwrap();
+        let err = iter.seek(&ymd_hms(2025, 1, 1, 0, 0, 0)).unwrap_err();
+        assert_eq!(
+            err,
+            Error::Overflow(Operation::Add, TimeType::moment(start), amount.clone())
+        );
+
+        let mut iter = Iter::build(start, amount).unwrap().backwards();
+        let err = iter.seek(&ymd_hms(2023, 1, 1, 0, 0, 0)).unwrap_err();
+        assert_eq!(err.kind(), crate::error::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_seek_backwards_matches_stepping() {
+        let increments = [
+            TimeType::minutes(15),
+            TimeType::days(1),
+            TimeType::days(40),
+            TimeType::months(1),
+            TimeType::months(7),
+            TimeType::years(1),
+        ];
+        let starts = [
+            ymd_hms(2003, 3, 28, 12, 0, 0),
+            ymd_hms(2003, 3, 31, 12, 0, 0),
+            ymd_hms(2003, 12, 1, 0, 0, 0),
+        ];
+        let moments = [ymd_hms(2001, 3, 4, 1, 2, 3), ymd_hms(2002, 1, 1, 0, 0, 0)];
+
+        for increment in increments.iter() {
+            for start in starts.iter() {
+                for moment in moments.iter() {
+                    let iter = || Iter::build(*start, increment.clone()).unwrap().backwards();
+                    let expected = brute_force(iter(), moment).unwrap();
+                    let found = seeked(iter(), moment);
+                    assert_eq!(found, expected, "{:?} from {} before {}", increment, start, moment);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_anchored {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn moments(iter: Iter, n: usize) -> Vec<NaiveDateTime> {
+        iter.take(n).map(|tt| *tt.unwrap().get_moment().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_monthly_from_the_31st() {
+        let iter = Iter::build(ymd_hms(2024, 1, 31, 8, 0, 0), TimeType::months(1))
+            .unwrap()
+            .anchored();
+        assert!(iter.is_anchored());
+
+        assert_eq!(
+            moments(iter, 5),
+            vec![
+                ymd_hms(2024, 1, 31, 8, 0, 0),
+                ymd_hms(2024, 2, 29, 8, 0, 0),
+                ymd_hms(2024, 3, 31, 8, 0, 0),
+                ymd_hms(2024, 4, 30, 8, 0, 0),
+                ymd_hms(2024, 5, 31, 8, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cumulative_drifts() {
+        let iter = Iter::build(ymd_hms(2024, 1, 31, 8, 0, 0), TimeType::months(1)).unwrap();
+        assert_eq!(
+            moments(iter, 3),
+            vec![
+                ymd_hms(2024, 1, 31, 8, 0, 0),
+                ymd_hms(2024, 3, 2, 8, 0, 0),
+                ymd_hms(2024, 4, 2, 8, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yearly_from_leap_day() {
+        let iter = Iter::build(ymd_hms(2024, 2, 29, 8, 0, 0), TimeType::years(1))
+            .unwrap()
+            .anchored();
+        assert_eq!(
+            moments(iter, 5),
+            vec![
+                ymd_hms(2024, 2, 29, 8, 0, 0),
+                ymd_hms(2025, 2, 28, 8, 0, 0),
+                ymd_hms(2026, 2, 28, 8, 0, 0),
+                ymd_hms(2027, 2, 28, 8, 0, 0),
+                ymd_hms(2028, 2, 29, 8, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_increment() {
+        let iter = Iter::build(ymd_hms(2024, 1, 31, 8, 0, 0), TimeType::weeks(2))
+            .unwrap()
+            .anchored();
+        assert_eq!(
+            moments(iter, 3),
+            vec![
+                ymd_hms(2024, 1, 31, 8, 0, 0),
+                ymd_hms(2024, 2, 14, 8, 0, 0),
+                ymd_hms(2024, 2, 28, 8, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backwards() {
+        let iter = Iter::build(ymd_hms(2024, 5, 31, 8, 0, 0), TimeType::months(1))
+            .unwrap()
+            .anchored()
+            .backwards();
+        assert_eq!(
+            moments(iter, 4),
+            vec![
+                ymd_hms(2024, 5, 31, 8, 0, 0),
+                ymd_hms(2024, 4, 30, 8, 0, 0),
+                ymd_hms(2024, 3, 31, 8, 0, 0),
+                ymd_hms(2024, 2, 29, 8, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut iter = Iter::build(ymd_hms(2015, 1, 31, 8, 0, 0), TimeType::months(1))
+            .unwrap()
+            .anchored();
+
+        let next = iter.next_after(&ymd_hms(2024, 6, 15, 8, 0, 0)).unwrap().unwrap();
+        assert_eq!(next.get_moment(), Some(&ymd_hms(2024, 6, 30, 8, 0, 0)));
+        assert_eq!(
+            iter.next().unwrap().unwrap().get_moment(),
+            Some(&ymd_hms(2024, 7, 31, 8, 0, 0))
+        );
+
+        let next = iter.next_after(&ymd_hms(2020, 1, 1, 8, 0, 0)).unwrap().unwrap();
+        assert_eq!(next.get_moment(), Some(&ymd_hms(2024, 8, 31, 8, 0, 0)));
+    }
+
+    #[test]
+    fn test_seek_matches_stepping() {
+        for increment in [
+            TimeType::hours(7),
+            TimeType::months(1),
+            TimeType::months(5),
+            TimeType::years(1),
+        ] {
+            for backwards in [false, true] {
+                let iter = || {
+                    let iter = Iter::build(ymd_hms(2020, 2, 29, 8, 0, 0), increment.clone())
+                        .unwrap()
+                        .anchored();
+                    if backwards {
+                        iter.backwards()
+                    } else {
+                        iter
+                    }
+                };
+                let moment = if backwards {
+                    ymd_hms(2015, 3, 3, 8, 0, 0)
+                } else {
+                    ymd_hms(2025, 3, 3, 8, 0, 0)
+                };
+
+                let expected = iter()
+                    .map(|tt| *tt.unwrap().get_moment().unwrap())
+                    .find(|m| if backwards { *m < moment } else { *m > moment })
+                    .unwrap();
+                let found = iter().next_after(&moment).unwrap().unwrap();
+
+                assert_eq!(found.get_moment(), Some(&expected), "{:?}", increment);
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        let start = ymd_hms(2024, 1, 1, 8, 0, 0);
+        let amount = TimeType::days(i64::MAX / 1000);
+
+        assert!(matches!(
+            nth_from_anchor(start, &amount, 1),
+            Err(Error::Overflow(Operation::Add, ..))
+        ));
+        assert!(matches!(
+            nth_from_anchor(start, &TimeType::months(i64::MAX), 2),
+            Err(Error::Overflow(..))
+        ));
+
+        let mut iter = Iter::build(start, amount.clone()).unwrap().anchored();
+        assert_eq!(iter.next().unwrap().unwrap(), TimeType::moment(start));
+        assert!(matches!(iter.next(), Some(Err(Error::Overflow(..)))));
+
+        let mut iter = Iter::build(start, amount).unwrap().anchored();
+        assert!(matches!(
+            iter.seek(&ymd_hms(2025, 1, 1, 8, 0, 0)),
+            Err(Error::Overflow(..))
+        ));
+    }
+
+    #[test]
+    fn test_months_regular() {
+        // the 31st exists in every month reached in steps of twelve from a long month
+        assert!(months_regular(1, 31, 12));
+        assert!(months_regular(10, 31, -24));
+        assert!(!months_regular(1, 31, 1));
+        assert!(!months_regular(3, 30, 11));
+        assert!(months_regular(3, 28, 7));
+        assert!(!months_regular(1, 29, 1));
+    }
+}
+
+#[cfg(test)]
+mod test_merge {
+    use super::extensions::*;
+    use super::*;
+    use crate::indicator::Day;
+    use crate::util::tests::ymd_hms;
+
+    type Source = Box<dyn Iterator<Item = Result<TimeType>>>;
+
+    fn items<T, I>(iter: MergeIter<T, I>) -> Vec<(T, NaiveDateTime)>
+    where
+        T: Clone,
+        I: Iterator<Item = Result<TimeType>>,
+    {
+        iter.map(|item| {
+            let (tag, tt) = item.unwrap();
+            (tag, *tt.get_moment().unwrap())
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_merge_chronologically() {
+        // 2024-01-01 is a Monday
+        let start = TimeType::moment(ymd_hms(2024, 1, 1, 0, 0, 0));
+        let end = ymd_hms(2024, 1, 10, 0, 0, 0);
+        let sources: Vec<(&str, Source)> = vec![
+            (
+                "mondays",
+                Box::new(start.clone().daily(1).unwrap().every(Day::Monday).until(end)),
+            ),
+            ("every 3 days", Box::new(start.clone().daily(3).unwrap().times(3))),
+            (
+                "noon",
+                Box::new(
+                    Iter::build(ymd_hms(2024, 1, 2, 12, 0, 0), TimeType::days(2))
+                        .unwrap()
+                        .times(2),
+                ),
+            ),
+        ];
+
+        assert_eq!(
+            items(MergeIter::tagged(sources)),
+            vec![
+                ("mondays", ymd_hms(2024, 1, 1, 0, 0, 0)),
+                ("every 3 days", ymd_hms(2024, 1, 1, 0, 0, 0)),
+                ("noon", ymd_hms(2024, 1, 2, 12, 0, 0)),
+                ("every 3 days", ymd_hms(2024, 1, 4, 0, 0, 0)),
+                ("noon", ymd_hms(2024, 1, 4, 12, 0, 0)),
+                ("every 3 days", ymd_hms(2024, 1, 7, 0, 0, 0)),
+                ("mondays", ymd_hms(2024, 1, 8, 0, 0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let sources = vec![
+            Iter::build(ymd_hms(2024, 1, 1, 0, 0, 0), TimeType::hours(2))
+                .unwrap()
+                .times(4),
+            Iter::build(ymd_hms(2024, 1, 1, 0, 0, 0), TimeType::hours(3))
+                .unwrap()
+                .times(3),
+        ];
+
+        assert_eq!(
+            items(MergeIter::new(sources).dedup()),
+            vec![
+                (0, ymd_hms(2024, 1, 1, 0, 0, 0)),
+                (0, ymd_hms(2024, 1, 1, 2, 0, 0)),
+                (1, ymd_hms(2024, 1, 1, 3, 0, 0)),
+                (0, ymd_hms(2024, 1, 1, 4, 0, 0)),
+                (0, ymd_hms(2024, 1, 1, 6, 0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_are_passed_through() {
+        let sources: Vec<Vec<Result<TimeType>>> = vec![
+            vec![Ok(TimeType::moment(ymd_hms(2024, 1, 1, 0, 0, 0)))],
+            vec![
+                Ok(TimeType::days(1)),
+                Ok(TimeType::moment(ymd_hms(2024, 1, 2, 0, 0, 0))),
+            ],
+        ];
+        let mut merged = MergeIter::new(sources.into_iter().map(Vec::into_iter));
+
+        assert!(matches!(merged.next(), Some(Err(Error::NotAMoment(_)))));
+        assert_eq!(merged.next().unwrap().unwrap().0, 0);
+        assert_eq!(merged.next().unwrap().unwrap().0, 1);
+        assert!(merged.next().is_none());
+    }
+
+    #[test]
+    fn test_empty() {
+        let sources: Vec<Iter> = Vec::new();
+        assert!(MergeIter::new(sources).next().is_none());
+    }
+
+    #[test]
+    fn test_untagged_chains_with_other_adapters() {
+        let sources = vec![
+            Iter::build(ymd_hms(2024, 1, 1, 9, 0, 0), TimeType::days(2)).unwrap(),
+            Iter::build(ymd_hms(2024, 1, 1, 18, 0, 0), TimeType::days(3)).unwrap(),
+        ];
+
+        let v = MergeIter::new(sources)
+            .untagged()
+            .every(Day::Thursday)
+            .until(ymd_hms(2024, 1, 12, 0, 0, 0))
+            .map(|tt| *tt.unwrap().get_moment().unwrap())
+            .collect::<Vec<_>>();
+
+        // 2024-01-04 and 2024-01-11 are Thursdays
+        assert_eq!(v, vec![ymd_hms(2024, 1, 4, 18, 0, 0), ymd_hms(2024, 1, 11, 9, 0, 0)]);
+    }
+}
+
+#[cfg(test)]
+mod test_intersect_except {
+    use super::extensions::*;
+    use super::*;
+    use crate::indicator::Day;
+    use crate::util::tests::ymd_hms;
+
+    fn moments<I: Iterator<Item = Result<TimeType>>>(iter: I) -> Vec<NaiveDateTime> {
+        iter.map(|tt| *tt.unwrap().get_moment().unwrap()).collect()
+    }
+
+    fn mondays_at_nine() -> impl Iterator<Item = Result<TimeType>> {
+        // 2024-01-01 is a Monday
+        Iter::build(ymd_hms(2024, 1, 1, 9, 0, 0), TimeType::weeks(1)).unwrap()
+    }
+
+    #[test]
+    fn test_paydays_on_mondays() {
+        let paydays = || {
+            Iter::build(ymd_hms(2024, 1, 15, 0, 0, 0), TimeType::months(1))
+                .unwrap()
+                .until(ymd_hms(2025, 1, 1, 0, 0, 0))
+        };
+
+        let v = moments(paydays().intersect(mondays_at_nine(), Tolerance::SameDay));
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 15, 0, 0, 0),
+                ymd_hms(2024, 4, 15, 0, 0, 0),
+                ymd_hms(2024, 7, 15, 0, 0, 0)
+            ]
+        );
+
+        let v = moments(paydays().intersect(mondays_at_nine(), Tolerance::SameInstant));
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_endless_inner_with_finite_other() {
+        let daily = || Iter::build(ymd_hms(2024, 1, 1, 9, 0, 0), TimeType::days(1)).unwrap();
+
+        let v = moments(daily().intersect(daily().times(2), Tolerance::SameInstant).take(3));
+        assert_eq!(v, vec![ymd_hms(2024, 1, 1, 9, 0, 0), ymd_hms(2024, 1, 2, 9, 0, 0)]);
+
+        let empty: Vec<Result<TimeType>> = Vec::new();
+        let v = moments(daily().intersect(empty.into_iter(), Tolerance::SameDay).take(1));
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_standups_except_reviews() {
+        // sprint reviews every other Friday, starting 2024-01-12
+        let reviews = Iter::build(ymd_hms(2024, 1, 12, 14, 0, 0), TimeType::weeks(2)).unwrap();
+        let standups = TimeType::moment(ymd_hms(2024, 1, 8, 9, 30, 0))
+            .daily(1)
+            .unwrap()
+            .without(Day::Saturday)
+            .without(Day::Sunday)
+            .times(10);
+
+        let v = moments(standups.except(reviews, Tolerance::SameDay));
+        assert_eq!(v.len(), 9);
+        assert!(!v.contains(&ymd_hms(2024, 1, 12, 9, 30, 0)));
+        assert!(v.contains(&ymd_hms(2024, 1, 19, 9, 30, 0)));
+    }
+
+    #[test]
+    fn test_within() {
+        let a = Iter::build(ymd_hms(2024, 1, 1, 8, 0, 0), TimeType::hours(1))
+            .unwrap()
+            .times(6);
+        let b = Iter::build(ymd_hms(2024, 1, 1, 10, 20, 0), TimeType::hours(2))
+            .unwrap()
+            .times(2);
+
+        let v = moments(a.intersect(b, Tolerance::Within(Duration::minutes(30))));
+        assert_eq!(v, vec![ymd_hms(2024, 1, 1, 10, 0, 0), ymd_hms(2024, 1, 1, 12, 0, 0)]);
+    }
+
+    #[test]
+    fn test_other_exhausted() {
+        let a = Iter::build(ymd_hms(2024, 1, 1, 0, 0, 0), TimeType::days(1))
+            .unwrap()
+            .times(5);
+        let b = Iter::build(ymd_hms(2024, 1, 2, 0, 0, 0), TimeType::days(1))
+            .unwrap()
+            .times(2);
+
+        let v = moments(a.except(b, Tolerance::SameInstant));
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 0, 0, 0),
+                ymd_hms(2024, 1, 4, 0, 0, 0),
+                ymd_hms(2024, 1, 5, 0, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_are_passed_through() {
+        let a = vec![
+            Ok(TimeType::moment(ymd_hms(2024, 1, 1, 0, 0, 0))),
+            Ok(TimeType::days(1)),
+        ];
+        let b = vec![Ok(TimeType::moment(ymd_hms(2024, 1, 1, 0, 0, 0)))];
+
+        let mut v = a.into_iter().intersect(b.into_iter(), Tolerance::SameInstant);
+        assert!(v.next().unwrap().is_ok());
+        assert!(matches!(v.next(), Some(Err(Error::NotAMoment(_)))));
+        assert!(v.next().is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_intervals {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn pairs<I: Iterator<Item = Result<Interval>>>(iter: I) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        iter.map(|i| i.unwrap()).map(|i| (*i.start(), *i.end())).collect()
+    }
+
+    fn meetings() -> Iter {
+        Iter::build(ymd_hms(2024, 1, 1, 10, 0, 0), TimeType::hours(4)).unwrap()
+    }
+
+    #[test]
+    fn test_amount() {
+        let v = pairs(meetings().times(2).with_length(TimeType::minutes(90)));
+        assert_eq!(
+            v,
+            vec![
+                (ymd_hms(2024, 1, 1, 10, 0, 0), ymd_hms(2024, 1, 1, 11, 30, 0)),
+                (ymd_hms(2024, 1, 1, 14, 0, 0), ymd_hms(2024, 1, 1, 15, 30, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_until_overhang() {
+        let end = ymd_hms(2024, 1, 1, 15, 0, 0);
+
+        let included = pairs(
+            meetings()
+                .until(end)
+                .intervals(TimeType::minutes(90), Overhang::Include),
+        );
+        assert_eq!(
+            included.last(),
+            Some(&(ymd_hms(2024, 1, 1, 14, 0, 0), ymd_hms(2024, 1, 1, 15, 30, 0)))
+        );
+
+        let clipped = pairs(meetings().until(end).intervals(TimeType::minutes(90), Overhang::Clip));
+        assert_eq!(clipped.len(), 2);
+        assert_eq!(clipped.last(), Some(&(ymd_hms(2024, 1, 1, 14, 0, 0), end)));
+    }
+
+    #[test]
+    fn test_until_mark_clipped() {
+        let evenings = Iter::build(ymd_hms(2024, 12, 30, 18, 0, 0), TimeType::days(1)).unwrap();
+        let v = pairs(
+            evenings
+                .until(Mark::EndOfYear)
+                .intervals(TimeType::hours(12), Overhang::Clip),
+        );
+        assert_eq!(v.len(), 2);
+        assert_eq!(
+            v.last(),
+            Some(&(ymd_hms(2024, 12, 31, 18, 0, 0), ymd_hms(2025, 1, 1, 0, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn test_clipped_ends_iteration() {
+        let v = pairs(
+            meetings()
+                .with_length(TimeType::hours(1))
+                .clipped(ymd_hms(2024, 1, 1, 14, 0, 0)),
+        );
+        assert_eq!(v, vec![(ymd_hms(2024, 1, 1, 10, 0, 0), ymd_hms(2024, 1, 1, 11, 0, 0))]);
+    }
+
+    #[test]
+    fn test_end_of_day_and_month() {
+        let v = pairs(meetings().times(3).with_length(Length::EndOfDay));
+        assert_eq!(v[2], (ymd_hms(2024, 1, 1, 18, 0, 0), ymd_hms(2024, 1, 2, 0, 0, 0)));
+
+        let v = pairs(
+            Iter::build(ymd_hms(2023, 12, 20, 8, 0, 0), TimeType::days(30))
+                .unwrap()
+                .times(2)
+                .with_length(Length::EndOfMonth),
+        );
+        assert_eq!(
+            v,
+            vec![
+                (ymd_hms(2023, 12, 20, 8, 0, 0), ymd_hms(2024, 1, 1, 0, 0, 0)),
+                (ymd_hms(2024, 1, 19, 8, 0, 0), ymd_hms(2024, 2, 1, 0, 0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let v = meetings()
+            .with_length(TimeType::moment(ymd_hms(2024, 1, 1, 0, 0, 0)))
+            .next();
+        assert!(matches!(v, Some(Err(Error::NotAnAmount(_)))));
+
+        let v = meetings().with_length(TimeType::hours(-1)).next();
+        assert!(matches!(v, Some(Err(Error::InvalidInterval(_, _)))));
+    }
+}
+
+#[cfg(test)]
+mod test_until_bounds {
+    use crate::util::tests::ymd;
+
+    use super::*;
+
+    fn days() -> Iter {
+        Iter::build(ymd(2024, 1, 1), TimeType::days(1)).unwrap()
+    }
+
+    #[test]
+    fn test_inclusive() {
+        let mut until = days().until(ymd(2024, 1, 5));
+        assert_eq!(until.bound(), None);
+        until.next();
+        assert_eq!(until.bound(), Some(&Bound::Exclusive(ymd(2024, 1, 5))));
+        assert_eq!(until.count(), 3);
+
+        let mut until = days().until(ymd(2024, 1, 5)).inclusive();
+        until.next();
+        assert_eq!(until.bound(), Some(&Bound::Inclusive(ymd(2024, 1, 5))));
+        assert_eq!(until.count(), 4);
+    }
+
+    #[test]
+    fn test_mark() {
+        let ending = Mark::Moment(TimeType::moment(ymd(2024, 1, 5)));
+        assert_eq!(days().until(ending).count(), 4);
+
+        // relative marks are resolved from the first moment
+        let mut until = Iter::build(ymd(2030, 6, 1), TimeType::days(1))
+            .unwrap()
+            .until(Mark::EndOfYear);
+        until.next();
+        assert_eq!(until.bound(), Some(&Bound::Exclusive(ymd(2031, 1, 1))));
+        assert_eq!(until.count(), 213);
+    }
+
+    #[test]
+    fn test_ending_not_resolved_before_iterating() {
+        let mut until = days().until(TimeType::days(1));
+        assert_eq!(until.next(), Some(Err(Error::NotAMoment(TimeType::days(1)))));
+        assert_eq!(until.next(), None);
+    }
+
+    #[test]
+    fn test_expression() {
+        let ending = TimeType::moment(ymd(2024, 1, 1)) + TimeType::weeks(3);
+        assert_eq!(days().until(ending).count(), 21);
+
+        // the end of the year is December 31st, 00:00
+        let ending = || TimeType::moment(ymd(2024, 1, 1)).end_of_year();
+        let v = days().until(ending()).collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(v.last().unwrap().get_moment(), Some(&ymd(2024, 12, 30)));
+
+        let v = days().until(ending()).inclusive().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(v.len(), 366);
+        assert_eq!(v.last().unwrap().get_moment(), Some(&ymd(2024, 12, 31)));
+    }
+
+    #[test]
+    fn test_expression_not_a_moment() {
+        let mut until = days().until(TimeType::days(3));
+        assert_eq!(until.bound(), None);
+        assert!(matches!(until.next(), Some(Err(Error::NotAMoment(_)))));
+        assert!(until.next().is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_occurrences {
+    use super::*;
+    use crate::indicator::Day;
+    use crate::indicator::Month;
+    use crate::matcher::from_fn;
+    use crate::timetype::TimeType as TT;
+    use crate::util::tests::moment;
+
+    #[test]
+    fn test_every_amount() {
+        let v = moment(2024, 1, 1)
+            .every(TT::weeks(1))
+            .take(3)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v, vec![moment(2024, 1, 1), moment(2024, 1, 8), moment(2024, 1, 15)]);
+    }
+
+    #[test]
+    fn test_every_matcher() {
+        let v = moment(2024, 1, 1)
+            .every(Day::Friday)
+            .take(2)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v, vec![moment(2024, 1, 5), moment(2024, 1, 12)]);
+    }
+
+    #[test]
+    fn test_skip_until_end_of_year() {
+        let v = moment(2024, 9, 2)
+            .every(TT::weeks(1))
+            .skip(Month::October)
+            .until(Mark::EndOfYear)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v.len(), 18 - 4);
+        assert_eq!(v[4], moment(2024, 9, 30));
+        assert_eq!(v[5], moment(2024, 11, 4));
+        assert_eq!(v.last(), Some(&moment(2024, 12, 30)));
+    }
+
+    #[test]
+    fn test_until_end_includes_the_last_day() {
+        let v = moment(2024, 12, 29)
+            .every(TT::days(1))
+            .until(Mark::EndOfYear)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            v,
+            vec![moment(2024, 12, 29), moment(2024, 12, 30), moment(2024, 12, 31)]
+        );
+
+        let v = moment(2024, 2, 27)
+            .every(TT::days(1))
+            .until(Mark::EndOfMonth)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(v, vec![moment(2024, 2, 27), moment(2024, 2, 28), moment(2024, 2, 29)]);
+    }
+
+    #[test]
+    fn test_until_expression() {
+        let days = |ending: TT| {
+            moment(2024, 1, 1)
+                .every(TT::days(1))
+                .until(ending)
+                .collect::<Result<Vec<_>>>()
+                .unwrap()
+        };
+        assert_eq!(days(moment(2024, 1, 1) + TT::weeks(3)).len(), 21);
+
+        let v = moment(2024, 1, 1)
+            .every(TT::days(1))
+            .until_inclusive(moment(2024, 1, 1) + TT::weeks(3))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(v.len(), 22);
+        assert_eq!(v.last(), Some(&moment(2024, 1, 22)));
+
+        let v = TT::today()
+            .every(TT::days(1))
+            .until(TT::today() + TT::weeks(3))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert!((21..=22).contains(&v.len()));
+    }
+
+    #[test]
+    fn test_until_inclusive_mark() {
+        let v = moment(2024, 1, 1)
+            .every(TT::months(1))
+            .until_inclusive(Mark::Moment(moment(2024, 3, 1)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(v, vec![moment(2024, 1, 1), moment(2024, 2, 1), moment(2024, 3, 1)]);
+
+        let err = moment(2024, 1, 1)
+            .every(TT::days(1))
+            .until_inclusive(TT::days(1))
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(err, Error::NotAMoment(TT::days(1)));
+    }
+
+    #[test]
+    fn test_until_month_start() {
+        let v = moment(2024, 2, 1)
+            .every(TT::days(10))
+            .until(Mark::StartOfMonth)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v, vec![moment(2024, 2, 1), moment(2024, 2, 11), moment(2024, 2, 21)]);
+    }
+
+    #[test]
+    fn test_until_stops_filtered_iteration() {
+        // no day matches, but the bound still ends the iteration
+        let v = moment(2024, 1, 1)
+            .every(Day::Monday)
+            .skip(Day::Monday)
+            .until(Mark::Moment(moment(2024, 3, 1)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_skip_closure() {
+        let v = (moment(2024, 3, 1) - TT::months(2))
+            .every(Day::Monday)
+            .skip(from_fn(|date| date.is(Mark::StartOfMonth)))
+            .until(Mark::Moment(moment(2024, 3, 1)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        // January 1st 2024 is a Monday
+        assert_eq!(v.first(), Some(&moment(2024, 1, 8)));
+        assert_eq!(v.last(), Some(&moment(2024, 2, 26)));
+        assert_eq!(v.len(), 8);
+    }
+
+    #[test]
+    fn test_skip_plain_closures() {
+        let on_moments = (moment(2024, 3, 1) - TT::months(2))
+            .every(Day::Monday)
+            .skip(|date: &NaiveDateTime| date.day() == 1)
+            .until(Mark::Moment(moment(2024, 3, 1)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        let on_timetypes = (moment(2024, 3, 1) - TT::months(2))
+            .every(Day::Monday)
+            .skip(|date: &TT| date.is(Mark::StartOfMonth))
+            .until(Mark::Moment(moment(2024, 3, 1)))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(on_moments.first(), Some(&moment(2024, 1, 8)));
+        assert_eq!(on_moments.len(), 8);
+        assert_eq!(on_moments, on_timetypes);
+    }
+
+    #[test]
+    fn test_errors_end_iteration() {
+        let v = TT::days(1).every(TT::weeks(1)).collect::<Vec<_>>();
+        assert_eq!(v, vec![Err(Error::NotAMoment(TT::days(1)))]);
+
+        let v = moment(2024, 1, 1).every(moment(2024, 1, 1)).collect::<Vec<_>>();
+        assert_eq!(v, vec![Err(Error::NotAnAmount(moment(2024, 1, 1)))]);
+
+        let v = moment(2024, 1, 1)
+            .every(TT::days(1))
+            .until(Mark::Moment(TT::days(1)))
+            .collect::<Vec<_>>();
+        assert_eq!(v, vec![Err(Error::NotAMoment(TT::days(1)))]);
+    }
+}
@@ -1,9 +1,22 @@
 #![recursion_limit = "256"]
 
+pub mod calendar_event;
+pub mod cron;
+pub mod difference;
 pub mod error;
+pub mod explain;
+pub mod fiscal;
 pub mod indicator;
+pub mod interval;
 pub mod iter;
 pub mod matcher;
 pub mod parser;
+pub mod recurrence;
 pub mod timetype;
+pub mod typed;
 mod util;
+
+// Make sure the examples in the README compile and run
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
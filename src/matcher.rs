@@ -1,33 +1,311 @@
 use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Timelike;
 #[cfg(feature = "with-filters")]
 use filters::filter::*;
 
 use crate::error::Error;
 use crate::error::Result;
+use crate::indicator::After;
+use crate::indicator::Before;
+use crate::indicator::Between;
 use crate::indicator::Day;
+use crate::indicator::DayOfMonth;
+use crate::indicator::DayOfYear;
+use crate::indicator::Hour;
+use crate::indicator::IsoWeek;
+use crate::indicator::Mark;
+use crate::indicator::Minute;
 use crate::indicator::Month;
+use crate::indicator::Year;
+use crate::interval::Interval;
 use crate::timetype::TimeType;
 
 /// A trait to extend indicator::* to be able to match them with a TimeType object
+///
+/// Matchers can be combined with `and_match()`, `or_match()` and `negate()`. Errors of the
+/// combined matchers are passed on, but evaluation stops as soon as the result is known.
+///
+/// With the "with-filters" feature, filters are matchers as well. The combinators are named
+/// differently from the ones of `filters::filter::Filter`, so both traits can be imported.
 pub trait Matcher {
     fn matches(&self, tt: &TimeType) -> Result<bool>;
+
+    /// Match if both `self` and `other` match
+    fn and_match<M>(self, other: M) -> And<Self, M>
+    where
+        Self: Sized,
+        M: Matcher,
+    {
+        And(self, other)
+    }
+
+    /// Match if `self` or `other` matches
+    fn or_match<M>(self, other: M) -> Or<Self, M>
+    where
+        Self: Sized,
+        M: Matcher,
+    {
+        Or(self, other)
+    }
+
+    /// Match if `self` does not match
+    fn negate(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+/// Matcher matching if both of two matchers match, see `Matcher::and_match()`
+#[derive(Clone, Debug)]
+pub struct And<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for And<A, B> {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        Ok(self.0.matches(tt)? && self.1.matches(tt)?)
+    }
+}
+
+/// Matcher matching if one of two matchers matches, see `Matcher::or_match()`
+#[derive(Clone, Debug)]
+pub struct Or<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for Or<A, B> {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        Ok(self.0.matches(tt)? || self.1.matches(tt)?)
+    }
+}
+
+/// Matcher matching if another matcher does not match, see `Matcher::negate()`
+#[derive(Clone, Debug)]
+pub struct Not<M>(M);
+
+impl<M: Matcher> Matcher for Not<M> {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        self.0.matches(tt).map(|b| !b)
+    }
+}
+
+/// Matcher matching if any of a list of matchers matches
+///
+/// An empty list never matches.
+#[derive(Clone, Debug)]
+pub struct AnyOf<M>(Vec<M>);
+
+impl<M: Matcher> AnyOf<M> {
+    pub fn new<I>(matchers: I) -> AnyOf<M>
+    where
+        I: IntoIterator<Item = M>,
+    {
+        AnyOf(matchers.into_iter().collect())
+    }
+}
+
+impl<M: Matcher> Matcher for AnyOf<M> {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        for m in self.0.iter() {
+            if m.matches(tt)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Matcher calling a closure, see `from_fn()`
+#[derive(Clone, Debug)]
+pub struct FromFn<F>(F);
+
+/// Use a closure as a matcher
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&TimeType) -> Result<bool>,
+{
+    FromFn(f)
+}
+
+impl<F> Matcher for FromFn<F>
+where
+    F: Fn(&TimeType) -> Result<bool>,
+{
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        (self.0)(tt)
+    }
+}
+
+/// Conversion into a boxed matcher, for methods accepting matchers as well as closures
+///
+/// Implemented for all matchers, for closures on moments (`Fn(&NaiveDateTime) -> bool`) and for
+/// closures like the ones passed to `from_fn()`. `Kind` only tells the implementations apart and is
+/// inferred. Closure parameters have to be annotated, as in `|date: &NaiveDateTime| ...`.
+pub trait IntoMatcher<Kind> {
+    fn into_matcher(self) -> Box<dyn Matcher>;
+}
+
+/// `IntoMatcher` kind of matchers
+pub enum MatcherKind {}
+
+/// `IntoMatcher` kind of closures on moments, which fail for everything else
+pub enum MomentFnKind {}
+
+/// `IntoMatcher` kind of closures on `TimeType`s, see `from_fn()`
+pub enum TimeTypeFnKind {}
+
+impl<M> IntoMatcher<MatcherKind> for M
+where
+    M: Matcher + 'static,
+{
+    fn into_matcher(self) -> Box<dyn Matcher> {
+        Box::new(self)
+    }
+}
+
+impl<F> IntoMatcher<MomentFnKind> for F
+where
+    F: Fn(&NaiveDateTime) -> bool + 'static,
+{
+    fn into_matcher(self) -> Box<dyn Matcher> {
+        Box::new(from_fn(move |tt| match_moment(tt, &self)))
+    }
+}
+
+impl<F> IntoMatcher<TimeTypeFnKind> for F
+where
+    F: Fn(&TimeType) -> Result<bool> + 'static,
+{
+    fn into_matcher(self) -> Box<dyn Matcher> {
+        Box::new(from_fn(self))
+    }
 }
 
 impl Matcher for Day {
     fn matches(&self, tt: &TimeType) -> Result<bool> {
-        let this: chrono::Weekday = self.clone().into();
-        tt.get_moment()
-            .map(|mom| this == mom.weekday())
-            .ok_or(Error::ArgumentErrorNotAMoment(tt.name()))
+        let this: chrono::Weekday = (*self).into();
+        tt.get_calendar_date()
+            .map(|date| this == date.weekday())
+            .ok_or_else(|| Error::NotAMoment(tt.clone()))
     }
 }
 
 impl Matcher for Month {
     fn matches(&self, tt: &TimeType) -> Result<bool> {
-        let this: u32 = self.clone().into();
-        tt.get_moment()
-            .map(|mom| this == mom.month())
-            .ok_or(Error::ArgumentErrorNotAMoment(tt.name()))
+        let this: u32 = (*self).into();
+        tt.get_calendar_date()
+            .map(|date| this == date.month())
+            .ok_or_else(|| Error::NotAMoment(tt.clone()))
+    }
+}
+
+// Match on the date of a moment or date, fail for everything else
+fn match_date<F>(tt: &TimeType, f: F) -> Result<bool>
+where
+    F: FnOnce(NaiveDate) -> bool,
+{
+    tt.get_calendar_date()
+        .map(f)
+        .ok_or_else(|| Error::NotAMoment(tt.clone()))
+}
+
+// Match on the time of day of a moment or time, fail for everything else
+fn match_time<F>(tt: &TimeType, f: F) -> Result<bool>
+where
+    F: FnOnce(NaiveTime) -> bool,
+{
+    tt.get_time_of_day().map(f).ok_or_else(|| Error::NotAMoment(tt.clone()))
+}
+
+impl Matcher for DayOfMonth {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_date(tt, |date| match self {
+            DayOfMonth::Nth(n) => date.day() == *n,
+            DayOfMonth::Last => date.succ_opt().map(|next| next.month()) != Some(date.month()),
+        })
+    }
+}
+
+impl Matcher for Hour {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_time(tt, |time| time.hour() == self.0)
+    }
+}
+
+impl Matcher for Minute {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_time(tt, |time| time.minute() == self.0)
+    }
+}
+
+impl Matcher for Year {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_date(tt, |date| date.year() == self.0)
+    }
+}
+
+impl Matcher for IsoWeek {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_date(tt, |date| date.iso_week().week() == self.0)
+    }
+}
+
+impl Matcher for DayOfYear {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_date(tt, |date| date.ordinal() == self.0)
+    }
+}
+
+// Match on the moment an expression evaluates to, fail for everything else
+fn match_moment<F>(tt: &TimeType, f: F) -> Result<bool>
+where
+    F: FnOnce(&NaiveDateTime) -> bool,
+{
+    match tt.clone().calculate()? {
+        TimeType::Moment(m) => Ok(f(&m)),
+        other => Err(Error::NotAMoment(other)),
+    }
+}
+
+impl Matcher for Before {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_moment(tt, |m| self.0.is_above(m))
+    }
+}
+
+impl Matcher for After {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_moment(tt, |m| self.0.is_below(m))
+    }
+}
+
+impl Matcher for Between {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_moment(tt, |m| self.contains(m))
+    }
+}
+
+impl Matcher for Mark {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match self {
+            Mark::StartOfYear => match_date(tt, |date| date.ordinal() == 1),
+            Mark::EndOfYear => match_date(tt, |date| date.month() == 12 && date.day() == 31),
+            Mark::StartOfMonth => match_date(tt, |date| date.day() == 1),
+            Mark::EndOfMonth => DayOfMonth::Last.matches(tt),
+            Mark::Moment(mark) => {
+                let mark = mark.clone().calculate()?;
+                let mark = *mark.get_moment().ok_or(Error::NotAMoment(mark.clone()))?;
+                match_moment(tt, |m| *m == mark)
+            },
+        }
+    }
+}
+
+/// Matches moments inside of the half-open interval
+impl Matcher for Interval {
+    fn matches(&self, tt: &TimeType) -> Result<bool> {
+        match_moment(tt, |m| self.contains(m))
     }
 }
 
@@ -40,3 +318,237 @@ where
         Ok(self.filter(tt))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicator::Bound;
+    use crate::iter::extensions::*;
+    use crate::iter::EveryFilter;
+    use crate::iter::WithoutFilter;
+    use crate::timetype::TimeType as TT;
+    use crate::util::tests::moment_hms;
+
+    #[test]
+    fn test_day_of_month() {
+        assert!(DayOfMonth::Nth(15).matches(&moment_hms(2024, 2, 15, 0, 0, 0)).unwrap());
+        assert!(!DayOfMonth::Nth(15).matches(&moment_hms(2024, 2, 16, 0, 0, 0)).unwrap());
+        assert!(DayOfMonth::Last.matches(&moment_hms(2024, 2, 29, 12, 0, 0)).unwrap());
+        assert!(!DayOfMonth::Last.matches(&moment_hms(2024, 2, 28, 12, 0, 0)).unwrap());
+        assert!(DayOfMonth::Last.matches(&moment_hms(2023, 2, 28, 12, 0, 0)).unwrap());
+        assert!(DayOfMonth::Last.matches(&moment_hms(2023, 12, 31, 12, 0, 0)).unwrap());
+    }
+
+    #[test]
+    fn test_hour_and_minute() {
+        let tt = moment_hms(2024, 2, 15, 9, 45, 0);
+        assert!(Hour(9).matches(&tt).unwrap());
+        assert!(!Hour(10).matches(&tt).unwrap());
+        assert!(Minute(45).matches(&tt).unwrap());
+        assert!(!Minute(0).matches(&tt).unwrap());
+
+        let time = TT::time(chrono::NaiveTime::from_hms_opt(9, 45, 0).unwrap());
+        assert!(Hour(9).matches(&time).unwrap());
+        assert!(Year(2024).matches(&time).is_err());
+    }
+
+    #[test]
+    fn test_year_week_and_day_of_year() {
+        let tt = moment_hms(2021, 1, 3, 0, 0, 0);
+        assert!(Year(2021).matches(&tt).unwrap());
+        // the first days of 2021 belong to week 53 of 2020
+        assert!(IsoWeek(53).matches(&tt).unwrap());
+        assert!(IsoWeek(1).matches(&moment_hms(2021, 1, 4, 0, 0, 0)).unwrap());
+        assert!(DayOfYear(3).matches(&tt).unwrap());
+        assert!(DayOfYear(366).matches(&moment_hms(2024, 12, 31, 0, 0, 0)).unwrap());
+    }
+
+    #[test]
+    fn test_not_a_moment() {
+        assert_eq!(
+            DayOfMonth::Last.matches(&TT::days(1)),
+            Err(Error::NotAMoment(TT::days(1)))
+        );
+        assert!(Hour(1).matches(&TT::days(1)).is_err());
+    }
+
+    #[test]
+    fn test_combinators() {
+        let tt = moment_hms(2024, 2, 15, 9, 0, 0);
+        assert!(Day::Thursday.and_match(Month::February).matches(&tt).unwrap());
+        assert!(!Day::Friday.and_match(Month::February).matches(&tt).unwrap());
+        assert!(Day::Friday.or_match(Month::February).matches(&tt).unwrap());
+        assert!(!Day::Friday.or_match(Month::March).matches(&tt).unwrap());
+        assert!(Day::Friday.negate().matches(&tt).unwrap());
+        assert!(AnyOf::new(vec![Hour(8), Hour(9)]).matches(&tt).unwrap());
+        assert!(!AnyOf::<Hour>::new(vec![]).matches(&tt).unwrap());
+    }
+
+    #[test]
+    fn test_combinators_propagate_errors() {
+        let amount = TT::days(1);
+        assert!(Day::Monday.and_match(Month::March).matches(&amount).is_err());
+        assert!(Day::Monday.or_match(Month::March).matches(&amount).is_err());
+        assert!(Day::Monday.negate().matches(&amount).is_err());
+        assert!(AnyOf::new(vec![Hour(8)]).matches(&amount).is_err());
+
+        // the second matcher is not evaluated once the result is known
+        let fail = from_fn(|tt| Err(Error::NotAMoment(tt.clone())));
+        let tt = moment_hms(2024, 2, 15, 9, 0, 0);
+        assert!(!Day::Monday.and_match(fail.clone()).matches(&tt).unwrap());
+        assert!(Day::Thursday.or_match(fail.clone()).matches(&tt).unwrap());
+        assert_eq!(Day::Thursday.and_match(fail).matches(&tt), Err(Error::NotAMoment(tt)));
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let working_hours = from_fn(|tt: &TT| {
+            tt.get_moment()
+                .map(|m| (9..17).contains(&m.hour()))
+                .ok_or_else(|| Error::NotAMoment(tt.clone()))
+        });
+
+        assert!(working_hours.matches(&moment_hms(2024, 2, 15, 9, 0, 0)).unwrap());
+        assert!(!working_hours.matches(&moment_hms(2024, 2, 15, 17, 0, 0)).unwrap());
+        assert!(working_hours.matches(&TT::days(1)).is_err());
+    }
+
+    #[test]
+    fn test_every_hour_between_on_days() {
+        // every hour, but only between 9 and 17 on the 1st and 15th
+        let v = moment_hms(2024, 3, 1, 0, 0, 0)
+            .hourly(1)
+            .unwrap()
+            .take(24 * 31)
+            .every(AnyOf::new((9..=17).map(Hour)))
+            .every(DayOfMonth::Nth(1).or_match(DayOfMonth::Nth(15)))
+            .without(Day::Saturday.or_match(Day::Sunday))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        // March 1st 2024 is a Friday, March 15th as well
+        assert_eq!(v.len(), 18);
+        assert_eq!(v[0], moment_hms(2024, 3, 1, 9, 0, 0));
+        assert_eq!(v[8], moment_hms(2024, 3, 1, 17, 0, 0));
+        assert_eq!(v[9], moment_hms(2024, 3, 15, 9, 0, 0));
+    }
+
+    #[test]
+    fn test_before_after() {
+        let bound = *moment_hms(2024, 3, 1, 0, 0, 0).get_moment().unwrap();
+        let at = moment_hms(2024, 3, 1, 0, 0, 0);
+        let earlier = moment_hms(2024, 2, 29, 23, 59, 59);
+
+        assert!(Before(Bound::Inclusive(bound)).matches(&at).unwrap());
+        assert!(!Before(Bound::Exclusive(bound)).matches(&at).unwrap());
+        assert!(Before(Bound::Exclusive(bound)).matches(&earlier).unwrap());
+        assert!(After(Bound::Inclusive(bound)).matches(&at).unwrap());
+        assert!(!After(Bound::Exclusive(bound)).matches(&at).unwrap());
+        assert!(!After(Bound::Inclusive(bound)).matches(&earlier).unwrap());
+    }
+
+    #[test]
+    fn test_before_evaluates_expression() {
+        let bound = *moment_hms(2024, 3, 1, 0, 0, 0).get_moment().unwrap();
+        let before = Before(Bound::Exclusive(bound));
+
+        assert!(before
+            .matches(&(moment_hms(2024, 2, 1, 0, 0, 0) + TT::days(28)))
+            .unwrap());
+        assert!(!before
+            .matches(&(moment_hms(2024, 2, 1, 0, 0, 0) + TT::months(1)))
+            .unwrap());
+        assert_eq!(before.matches(&TT::days(1)), Err(Error::NotAMoment(TT::days(1))));
+    }
+
+    #[test]
+    fn test_between() {
+        let start = *moment_hms(2024, 3, 1, 0, 0, 0).get_moment().unwrap();
+        let end = *moment_hms(2024, 3, 2, 0, 0, 0).get_moment().unwrap();
+
+        let inclusive = Between::inclusive(start, end).unwrap();
+        let exclusive = Between::exclusive(start, end).unwrap();
+        let half_open = Between::new(Bound::Inclusive(start), Bound::Exclusive(end)).unwrap();
+
+        for (tt, expected) in [
+            (moment_hms(2024, 2, 29, 23, 59, 59), [false, false, false]),
+            (moment_hms(2024, 3, 1, 0, 0, 0), [true, false, true]),
+            (moment_hms(2024, 3, 1, 12, 0, 0), [true, true, true]),
+            (moment_hms(2024, 3, 2, 0, 0, 0), [true, false, false]),
+        ] {
+            let actual = [
+                inclusive.matches(&tt).unwrap(),
+                exclusive.matches(&tt).unwrap(),
+                half_open.matches(&tt).unwrap(),
+            ];
+            assert_eq!(actual, expected, "{}", tt);
+        }
+
+        assert_eq!(Between::inclusive(end, start), Err(Error::InvalidInterval(end, start)));
+    }
+
+    #[test]
+    fn test_without_vacation() {
+        let start = *moment_hms(2024, 7, 8, 0, 0, 0).get_moment().unwrap();
+        let end = *moment_hms(2024, 7, 19, 23, 59, 59).get_moment().unwrap();
+        let vacation = Between::inclusive(start, end).unwrap();
+
+        let v = moment_hms(2024, 7, 1, 0, 0, 0)
+            .weekly(1)
+            .unwrap()
+            .take(5)
+            .without(vacation)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            v,
+            vec![
+                moment_hms(2024, 7, 1, 0, 0, 0),
+                moment_hms(2024, 7, 22, 0, 0, 0),
+                moment_hms(2024, 7, 29, 0, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        let start = *moment_hms(2024, 3, 1, 0, 0, 0).get_moment().unwrap();
+        let end = *moment_hms(2024, 3, 2, 0, 0, 0).get_moment().unwrap();
+        let interval = Interval::new(start, end).unwrap();
+
+        assert!(interval.matches(&moment_hms(2024, 3, 1, 0, 0, 0)).unwrap());
+        assert!(!interval.matches(&moment_hms(2024, 3, 2, 0, 0, 0)).unwrap());
+    }
+
+    #[test]
+    fn test_mark() {
+        let tt = moment_hms(2024, 12, 31, 12, 0, 0);
+        assert!(Mark::EndOfYear.matches(&tt).unwrap());
+        assert!(Mark::EndOfMonth.matches(&tt).unwrap());
+        assert!(!Mark::StartOfMonth.matches(&tt).unwrap());
+        assert!(Mark::StartOfMonth.matches(&moment_hms(2024, 2, 1, 8, 0, 0)).unwrap());
+        assert!(Mark::StartOfYear.matches(&moment_hms(2024, 1, 1, 8, 0, 0)).unwrap());
+        assert!(!Mark::StartOfYear.matches(&moment_hms(2024, 2, 1, 8, 0, 0)).unwrap());
+
+        let mark = Mark::Moment(moment_hms(2024, 12, 30, 12, 0, 0) + TT::days(1));
+        assert!(mark.matches(&tt).unwrap());
+        assert!(!mark.matches(&moment_hms(2024, 12, 31, 12, 0, 1)).unwrap());
+        assert!(Mark::Moment(TT::days(1)).matches(&tt).is_err());
+    }
+
+    #[test]
+    fn test_filter_iterator() {
+        let v = moment_hms(2024, 3, 1, 0, 0, 0)
+            .hourly(1)
+            .unwrap()
+            .take(24 * 31)
+            .every(DayOfMonth::Last)
+            .every(Hour(9))
+            .without(Minute(30))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v, vec![moment_hms(2024, 3, 31, 9, 0, 0)]);
+    }
+}
@@ -42,10 +42,9 @@ pub enum Iterspec {
 
 pub fn until_spec(i: &[u8]) -> IResult<&[u8], UntilSpec> {
     complete(alt((
-        map(
-            tuple((tag("until"), multispace1, exact_date_parser)),
-            |(_, _, exact)| UntilSpec::Exact(exact),
-        ),
+        map(tuple((tag("until"), multispace1, until_date)), |(_, _, date)| {
+            UntilSpec::Exact(date)
+        }),
         map(tuple((integer, multispace1, tag("times"))), |(num, _, _)| {
             UntilSpec::Times(num)
         }),
@@ -54,7 +53,7 @@ pub fn until_spec(i: &[u8]) -> IResult<&[u8], UntilSpec> {
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum UntilSpec {
-    Exact(ExactDate),
+    Exact(Date),
     Times(i64),
 }
 
@@ -102,11 +101,8 @@ impl Iterator {
         };
 
         let into_ndt = |e: timetype::TimeType| {
-            e.calculate()?
-                .get_moment()
-                .ok_or(Error::NotADateInsideIterator)
-                .map_err(Error::from)
-                .map(Clone::clone)
+            let e = e.calculate()?;
+            e.get_moment().copied().ok_or(Error::NotAMoment(e))
         };
 
         match self.2 {
@@ -408,4 +404,49 @@ mod tests {
             }
         }
     }
+
+    fn count_until(input: &str) -> (usize, Option<timetype::TimeType>) {
+        let (rest, i) = iterator(input.as_bytes()).unwrap();
+        assert!(rest.is_empty(), "Not parsed completely: {:?}", rest);
+
+        let v = i
+            .into_user_iterator()
+            .unwrap()
+            .take(1000)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        (v.len(), v.last().cloned())
+    }
+
+    #[test]
+    fn test_until_year_only() {
+        let (n, last) = count_until("2017-01-01 daily until 2018");
+        assert_eq!(n, 365);
+        assert_eq!(last.unwrap().get_moment().unwrap().ordinal(), 365);
+    }
+
+    #[test]
+    fn test_until_year_and_month() {
+        let (n, last) = count_until("2017-01-01 daily until 2017-03");
+        assert_eq!(n, 59);
+        assert_eq!(last.unwrap().get_moment().unwrap().day(), 28);
+    }
+
+    #[test]
+    fn test_until_expression() {
+        let (n, _) = count_until("2017-01-01 daily until 2017-01-01 + 1 week");
+        assert_eq!(n, 7);
+
+        let (n, _) = count_until("2017-01-01 monthly until 2017 + 2 years");
+        assert_eq!(n, 24);
+
+        let (n, _) = count_until("today daily until today + 3 weeks");
+        assert_eq!(n, 21);
+    }
+
+    #[test]
+    fn test_until_invalid_month() {
+        let res = until_spec(&b"until 2017-13"[..]);
+        assert!(res.is_err(), "Should not parse: {:?}", res);
+    }
 }
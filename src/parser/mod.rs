@@ -30,7 +30,10 @@
 //! ## User-facing syntax nodes
 //!
 //! AmountExpr = <Amount> (<Operator> <AmountExpr>)?
-//! ExactDate  = "today" | "yesterday" | "tomorrow" | <Iso8601>
+//! Timestamp  = "@" "-"? <Number>
+//! TimeOfDay  = "at" <HH> ":" <MM> (":" <SS>)?
+//! DateOnly   = "on" <Iso8601Date>
+//! ExactDate  = "today" | "yesterday" | "tomorrow" | <TimeOfDay> | <DateOnly> | <Timestamp> | <Iso8601>
 //! Date       = <ExactDate> (<Operator> <AmountExpr>)?
 //! Iterator   = <Date> <Iterspec> ("until" <ExactDate> | <number> "times")?
 //!
@@ -70,11 +73,53 @@ fn do_parse(input: &[u8]) -> IResult<&[u8], Result<Parsed>> {
     )))(input)
 }
 
+/// Parse user input
+///
+/// Syntax errors are reported as `Error::Parse` with the byte offset into `s` at which the parser
+/// failed. Input which is left over after a complete expression is a syntax error, too.
 pub fn parse(s: &str) -> Result<Parsed> {
-    match do_parse(s.as_bytes()) {
+    let input = s.as_bytes();
+    match do_parse(input) {
+        Ok((rest, _)) if !rest.iter().all(u8::is_ascii_whitespace) => Err(Error::Parse {
+            position: input.len() - rest.len(),
+            message: String::from("unexpected input"),
+        }),
         Ok((_, Ok(o))) => Ok(o),
         Ok((_, Err(e))) => Err(e),
-        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(Error::NomError(e.code.description().to_string())),
-        Err(nom::Err::Incomplete(_)) => Err(Error::UnknownParserError),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(Error::Parse {
+            position: input.len() - e.input.len(),
+            message: e.code.description().to_string(),
+        }),
+        Err(nom::Err::Incomplete(_)) => Err(Error::Parse {
+            position: input.len(),
+            message: String::from("incomplete input"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_parse_error_position() {
+        let err = parse("2024-01-01 + 1 fortnight").err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::Parse);
+        assert_eq!(err.code(), "parse/syntax");
+        assert_eq!(err.position(), Some(10));
+    }
+
+    #[test]
+    fn test_parse_error_position_at_start() {
+        let err = parse("whenever").err().unwrap();
+        assert_eq!(err.position(), Some(0));
+    }
+
+    #[test]
+    fn test_evaluation_error_has_no_position() {
+        let err = parse("2024-02-30").err().unwrap();
+        assert_eq!(err, Error::OutOfRange(2024, 2, 30, 0, 0, 0));
+        assert_eq!(err.position(), None);
     }
 }
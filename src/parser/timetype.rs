@@ -1,12 +1,14 @@
 use std::str;
 
 use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Weekday;
 use iso8601::parsers::{parse_date, parse_datetime};
 use nom::branch::alt;
-use nom::bytes::complete::tag;
+use nom::bytes::complete::{tag, take_while_m_n};
 use nom::character::complete::{digit1, multispace0, multispace1};
-use nom::combinator::{complete, map, map_opt, opt};
-use nom::sequence::{delimited, tuple};
+use nom::combinator::{complete, map, map_opt, opt, recognize};
+use nom::sequence::{delimited, preceded, tuple};
 use nom::IResult;
 
 use crate::error::Error;
@@ -153,6 +155,37 @@ impl IntoTimeType for AmountExpr {
     }
 }
 
+pub fn timestamp(input: &[u8]) -> IResult<&[u8], i64> {
+    map_opt(
+        tuple((tag("@"), recognize(tuple((opt(tag("-")), digit1))))),
+        |(_, digits)| str::from_utf8(digits).ok().and_then(|s| s.parse().ok()),
+    )(input)
+}
+
+fn two_digits(input: &[u8]) -> IResult<&[u8], u32> {
+    map_opt(take_while_m_n(2, 2, |c: u8| c.is_ascii_digit()), |digits| {
+        str::from_utf8(digits).ok().and_then(|s| s.parse().ok())
+    })(input)
+}
+
+pub fn time_of_day(input: &[u8]) -> IResult<&[u8], NaiveTime> {
+    map_opt(
+        tuple((
+            tag("at"),
+            multispace1,
+            two_digits,
+            tag(":"),
+            two_digits,
+            opt(preceded(tag(":"), two_digits)),
+        )),
+        |(_, _, hour, _, minute, second)| NaiveTime::from_hms_opt(hour, minute, second.unwrap_or(0)),
+    )(input)
+}
+
+pub fn date_only(input: &[u8]) -> IResult<&[u8], iso8601::Date> {
+    map(tuple((tag("on"), multispace1, parse_date)), |(_, _, date)| date)(input)
+}
+
 // The order is relevant here, because datetime is longer than date, we must parse datetime before
 // date.
 pub fn exact_date_parser(input: &[u8]) -> IResult<&[u8], ExactDate> {
@@ -160,16 +193,38 @@ pub fn exact_date_parser(input: &[u8]) -> IResult<&[u8], ExactDate> {
         map(tag("today"), |_| ExactDate::Today),
         map(tag("yesterday"), |_| ExactDate::Yesterday),
         map(tag("tomorrow"), |_| ExactDate::Tomorrow),
+        map(time_of_day, ExactDate::TimeOfDay),
+        map(date_only, ExactDate::DateOnly),
+        map(timestamp, ExactDate::Timestamp),
         map(parse_datetime, ExactDate::Iso8601DateTime),
         map(parse_date, ExactDate::Iso8601Date),
     )))(input)
 }
 
+// A year, optionally with a month, meaning the start of it
+//
+// This is not part of `exact_date_parser`, where "1000 seconds" would be read as a year.
+fn partial_date_parser(input: &[u8]) -> IResult<&[u8], ExactDate> {
+    map_opt(
+        tuple((two_digits, two_digits, opt(preceded(tag("-"), two_digits)))),
+        |(century, year, month)| {
+            let month = month.unwrap_or(1);
+            let year = (century * 100 + year) as i32;
+            (1..=12)
+                .contains(&month)
+                .then_some(ExactDate::Iso8601Date(iso8601::Date::YMD { year, month, day: 1 }))
+        },
+    )(input)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ExactDate {
     Today,
     Yesterday,
     Tomorrow,
+    Timestamp(i64),
+    TimeOfDay(NaiveTime),
+    DateOnly(iso8601::Date),
     Iso8601Date(iso8601::Date),
     Iso8601DateTime(iso8601::DateTime),
 }
@@ -180,15 +235,32 @@ impl IntoTimeType for ExactDate {
             ExactDate::Today => Ok(crate::timetype::TimeType::today()),
             ExactDate::Yesterday => Ok(crate::timetype::TimeType::today() - crate::timetype::TimeType::days(1)),
             ExactDate::Tomorrow => Ok(crate::timetype::TimeType::today() + crate::timetype::TimeType::days(1)),
+            ExactDate::Timestamp(secs) => crate::timetype::TimeType::from_timestamp(secs),
+            ExactDate::TimeOfDay(time) => Ok(crate::timetype::TimeType::time(time)),
+            ExactDate::DateOnly(date) => {
+                let nd = match date {
+                    iso8601::Date::YMD { year, month, day } => NaiveDate::from_ymd_opt(year, month, day)
+                        .ok_or(Error::OutOfRange(year.into(), month, day, 0, 0, 0)),
+                    iso8601::Date::Week { year, ww, d } => d
+                        .checked_sub(1)
+                        .and_then(|d| Weekday::try_from(d as u8).ok())
+                        .and_then(|weekday| NaiveDate::from_isoywd_opt(year, ww, weekday))
+                        .ok_or(Error::OutOfRange(year.into(), 1, 1, 0, 0, 0)),
+                    iso8601::Date::Ordinal { year, ddd } => {
+                        NaiveDate::from_yo_opt(year, ddd).ok_or(Error::OutOfRange(year.into(), 1, ddd, 0, 0, 0))
+                    },
+                }?;
+                Ok(crate::timetype::TimeType::date(nd))
+            },
             ExactDate::Iso8601Date(date) => match date {
                 iso8601::Date::YMD { year, month, day } => NaiveDate::from_ymd_opt(year, month, day)
                     .and_then(|ndt| ndt.and_hms_opt(0, 0, 0))
-                    .ok_or(Error::OutOfBounds(year, month, day, 0, 0, 0))
+                    .ok_or(Error::OutOfRange(year.into(), month, day, 0, 0, 0))
                     .map(crate::timetype::TimeType::moment),
 
                 iso8601::Date::Week { year, ww, d } => NaiveDate::from_ymd_opt(year, 1, 1)
                     .and_then(|ndt| ndt.and_hms_opt(0, 0, 0))
-                    .ok_or(Error::OutOfBounds(year, 1, 1, 0, 0, 0))
+                    .ok_or(Error::OutOfRange(year.into(), 1, 1, 0, 0, 0))
                     .map(crate::timetype::TimeType::moment)
                     .map(|m| {
                         m + crate::timetype::TimeType::weeks(ww as i64) + crate::timetype::TimeType::days(d as i64)
@@ -196,7 +268,7 @@ impl IntoTimeType for ExactDate {
 
                 iso8601::Date::Ordinal { year, ddd } => NaiveDate::from_ymd_opt(year, 1, 1)
                     .and_then(|ndt| ndt.and_hms_opt(0, 0, 0))
-                    .ok_or(Error::OutOfBounds(year, 1, 1, 0, 0, 0))
+                    .ok_or(Error::OutOfRange(year.into(), 1, 1, 0, 0, 0))
                     .map(crate::timetype::TimeType::moment)
                     .map(|m| m + crate::timetype::TimeType::days(ddd as i64)),
             },
@@ -206,12 +278,12 @@ impl IntoTimeType for ExactDate {
                 match date {
                     iso8601::Date::YMD { year, month, day } => NaiveDate::from_ymd_opt(year, month, day)
                         .and_then(|ndt| ndt.and_hms_opt(hour, minute, second))
-                        .ok_or(Error::OutOfBounds(year, month, day, hour, minute, second))
+                        .ok_or(Error::OutOfRange(year.into(), month, day, hour, minute, second))
                         .map(crate::timetype::TimeType::moment),
 
                     iso8601::Date::Week { year, ww, d } => NaiveDate::from_ymd_opt(year, 1, 1)
                         .and_then(|ndt| ndt.and_hms_opt(0, 0, 0))
-                        .ok_or(Error::OutOfBounds(year, 1, 1, 0, 0, 0))
+                        .ok_or(Error::OutOfRange(year.into(), 1, 1, 0, 0, 0))
                         .map(crate::timetype::TimeType::moment)
                         .map(|m| {
                             m + crate::timetype::TimeType::weeks(ww as i64)
@@ -223,7 +295,7 @@ impl IntoTimeType for ExactDate {
 
                     iso8601::Date::Ordinal { year, ddd } => NaiveDate::from_ymd_opt(year, 1, 1)
                         .and_then(|ndt| ndt.and_hms_opt(0, 0, 0))
-                        .ok_or(Error::OutOfBounds(year, 1, 1, 0, 0, 0))
+                        .ok_or(Error::OutOfRange(year.into(), 1, 1, 0, 0, 0))
                         .map(crate::timetype::TimeType::moment)
                         .map(|m| {
                             m + crate::timetype::TimeType::days(ddd as i64)
@@ -238,9 +310,21 @@ impl IntoTimeType for ExactDate {
 }
 
 pub fn date(input: &[u8]) -> IResult<&[u8], Date> {
+    date_with(exact_date_parser, input)
+}
+
+/// A date expression bounding an iterator, where a year or a year and a month suffice
+pub fn until_date(input: &[u8]) -> IResult<&[u8], Date> {
+    date_with(alt((exact_date_parser, partial_date_parser)), input)
+}
+
+fn date_with<'a, P>(exact: P, input: &'a [u8]) -> IResult<&'a [u8], Date>
+where
+    P: FnMut(&'a [u8]) -> IResult<&'a [u8], ExactDate>,
+{
     map(
         tuple((
-            exact_date_parser,
+            exact,
             opt(complete(map(
                 tuple((multispace1, operator_parser, multispace1, amount_expr)),
                 |(_, op, _, a)| (op, a),
@@ -433,6 +517,9 @@ mod tests {
                 _ => panic!("Unexpected enum variant"),
             },
             ExactDate::Tomorrow => panic!("Unexpected enum variant"),
+            ExactDate::Timestamp(_) => panic!("Unexpected enum variant"),
+            ExactDate::TimeOfDay(_) => panic!("Unexpected enum variant"),
+            ExactDate::DateOnly(_) => panic!("Unexpected enum variant"),
             ExactDate::Yesterday => panic!("Unexpected enum variant"),
             ExactDate::Today => panic!("Unexpected enum variant"),
         };
@@ -460,11 +547,94 @@ mod tests {
             },
             ExactDate::Iso8601Date(_) => panic!("Unexpected enum variant"),
             ExactDate::Tomorrow => panic!("Unexpected enum variant"),
+            ExactDate::Timestamp(_) => panic!("Unexpected enum variant"),
+            ExactDate::TimeOfDay(_) => panic!("Unexpected enum variant"),
+            ExactDate::DateOnly(_) => panic!("Unexpected enum variant"),
             ExactDate::Yesterday => panic!("Unexpected enum variant"),
             ExactDate::Today => panic!("Unexpected enum variant"),
         };
     }
 
+    #[test]
+    fn test_parse_expressions_timestamp() {
+        assert_eq!(
+            exact_date_parser(&b"@1700000000"[..]),
+            Ok((&b""[..], ExactDate::Timestamp(1700000000)))
+        );
+        assert_eq!(
+            exact_date_parser(&b"@-86400"[..]),
+            Ok((&b""[..], ExactDate::Timestamp(-86400)))
+        );
+        assert!(exact_date_parser(&b"@"[..]).is_err());
+    }
+
+    #[test]
+    fn test_parse_expressions_time_of_day() {
+        assert_eq!(
+            exact_date_parser(&b"at 14:30"[..]),
+            Ok((
+                &b""[..],
+                ExactDate::TimeOfDay(NaiveTime::from_hms_opt(14, 30, 0).unwrap())
+            ))
+        );
+        assert_eq!(
+            exact_date_parser(&b"at 07:05:09"[..]),
+            Ok((
+                &b""[..],
+                ExactDate::TimeOfDay(NaiveTime::from_hms_opt(7, 5, 9).unwrap())
+            ))
+        );
+        assert!(exact_date_parser(&b"at 25:00"[..]).is_err());
+        assert!(exact_date_parser(&b"at 1:00"[..]).is_err());
+    }
+
+    #[test]
+    fn test_time_of_day_expression() {
+        let (_, o) = timetype(&b"at 23:30 + 2hours"[..]).unwrap();
+        let calc_res = o.into_timetype().unwrap().calculate().unwrap();
+        assert_eq!(
+            calc_res,
+            crate::timetype::TimeType::time(NaiveTime::from_hms_opt(1, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_date_only_expression() {
+        let (_, o) = timetype(&b"on 2024-01-31 + 1month"[..]).unwrap();
+        let calc_res = o.into_timetype().unwrap().calculate().unwrap();
+        assert_eq!(
+            calc_res,
+            crate::timetype::TimeType::date(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap())
+        );
+
+        let (_, o) = timetype(&b"on 2024-W01-1"[..]).unwrap();
+        let calc_res = o.into_timetype().unwrap().calculate().unwrap();
+        assert_eq!(
+            calc_res,
+            crate::timetype::TimeType::date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+        );
+
+        let (_, o) = timetype(&b"on 2024-02-30"[..]).unwrap();
+        assert!(o.into_timetype().is_err());
+    }
+
+    #[test]
+    fn test_timestamp_date() {
+        let res = date(&b"@1700000000 + 1hour"[..]);
+        assert!(res.is_ok(), "Not done: {:?}", res);
+        let (_, o) = res.unwrap();
+
+        let calc_res: crate::timetype::TimeType = o.into_timetype().unwrap();
+        let calc_res = calc_res.calculate().unwrap();
+
+        assert_eq!(calc_res.get_moment().unwrap().year(), 2023);
+        assert_eq!(calc_res.get_moment().unwrap().month(), 11);
+        assert_eq!(calc_res.get_moment().unwrap().day(), 14);
+        assert_eq!(calc_res.get_moment().unwrap().hour(), 23);
+        assert_eq!(calc_res.get_moment().unwrap().minute(), 13);
+        assert_eq!(calc_res.get_moment().unwrap().second(), 20);
+    }
+
     #[test]
     fn test_simple_date_1() {
         let res = exact_date_parser(&b"today"[..]);
@@ -0,0 +1,1399 @@
+//! The module for recurrence rules as defined by RFC 5545 (iCalendar)
+//!
+//! A `RRule` describes how an event repeats, for example "the last weekday of every month":
+//!
+//! ```text
+//! FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1
+//! ```
+//!
+//! Rules can be parsed from and displayed as the RRULE text format. `RRule::occurrences()`
+//! generates the moments of a rule from a start moment. Like in most implementations, the start
+//! moment itself is only yielded if it matches the rule.
+//!
+//! A `RecurrenceSet` combines several rules with extra dates (RDATE) and removes excluded dates
+//! (EXDATE) and the occurrences of exclusion rules (EXRULE), like a calendar event with exceptions.
+
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Timelike;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::indicator::Day;
+use crate::indicator::Month;
+use crate::indicator::Year;
+use crate::timetype::TimeType;
+use crate::util::days_in_month;
+use crate::util::parse_error;
+
+/// If no occurrence was found for this many years, the rule is considered exhausted
+const MAX_YEARS_WITHOUT_OCCURRENCE: i32 = 400;
+
+const DAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// The unit of the periods a rule repeats in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn name(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A BYDAY entry: a weekday, optionally restricted to its n-th occurrence in the month or year
+///
+/// Negative ordinals count from the end, so `ByDay::nth(-1, Day::Friday)` is the last Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByDay {
+    ordinal: Option<i32>,
+    day: Day,
+}
+
+impl ByDay {
+    pub fn every(day: Day) -> ByDay {
+        ByDay { ordinal: None, day }
+    }
+
+    pub fn nth(ordinal: i32, day: Day) -> ByDay {
+        ByDay {
+            ordinal: Some(ordinal),
+            day,
+        }
+    }
+
+    pub fn ordinal(&self) -> Option<i32> {
+        self.ordinal
+    }
+
+    pub fn day(&self) -> Day {
+        self.day
+    }
+}
+
+impl fmt::Display for ByDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(n) = self.ordinal {
+            write!(f, "{}", n)?;
+        }
+        f.write_str(day_code(self.day))
+    }
+}
+
+/// A recurrence rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<ByDay>,
+    by_month_day: Vec<i32>,
+    by_month: Vec<Month>,
+    by_year_day: Vec<i32>,
+    by_week_no: Vec<i32>,
+    by_hour: Vec<u32>,
+    by_minute: Vec<u32>,
+    by_set_pos: Vec<i32>,
+    week_start: Day,
+}
+
+impl RRule {
+    /// A rule repeating every period of `freq`, without any restrictions
+    pub fn new(freq: Frequency) -> RRule {
+        RRule {
+            freq,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_year_day: Vec::new(),
+            by_week_no: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Day::Monday,
+        }
+    }
+
+    /// Repeat every `interval` periods instead of every period
+    pub fn with_interval(self, interval: u32) -> RRule {
+        RRule { interval, ..self }
+    }
+
+    /// End after `count` occurrences
+    pub fn with_count(self, count: u32) -> RRule {
+        RRule {
+            count: Some(count),
+            ..self
+        }
+    }
+
+    /// End after `until`, which is included if it is an occurrence
+    pub fn with_until(self, until: NaiveDateTime) -> RRule {
+        RRule {
+            until: Some(until),
+            ..self
+        }
+    }
+
+    pub fn with_by_day(self, by_day: Vec<ByDay>) -> RRule {
+        RRule { by_day, ..self }
+    }
+
+    pub fn with_by_month_day(self, by_month_day: Vec<i32>) -> RRule {
+        RRule { by_month_day, ..self }
+    }
+
+    pub fn with_by_month(self, by_month: Vec<Month>) -> RRule {
+        RRule { by_month, ..self }
+    }
+
+    pub fn with_by_year_day(self, by_year_day: Vec<i32>) -> RRule {
+        RRule { by_year_day, ..self }
+    }
+
+    pub fn with_by_week_no(self, by_week_no: Vec<i32>) -> RRule {
+        RRule { by_week_no, ..self }
+    }
+
+    pub fn with_by_hour(self, by_hour: Vec<u32>) -> RRule {
+        RRule { by_hour, ..self }
+    }
+
+    pub fn with_by_minute(self, by_minute: Vec<u32>) -> RRule {
+        RRule { by_minute, ..self }
+    }
+
+    /// Only keep the n-th occurrences of each period, negative positions count from the end
+    pub fn with_by_set_pos(self, by_set_pos: Vec<i32>) -> RRule {
+        RRule { by_set_pos, ..self }
+    }
+
+    /// The first day of a week, Monday by default
+    pub fn with_week_start(self, week_start: Day) -> RRule {
+        RRule { week_start, ..self }
+    }
+
+    pub fn freq(&self) -> Frequency {
+        self.freq
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    pub fn until(&self) -> Option<&NaiveDateTime> {
+        self.until.as_ref()
+    }
+
+    /// Check the rule for values RFC 5545 does not allow
+    pub fn validate(&self) -> Result<()> {
+        fn check<T: Copy>(values: &[T], name: &str, valid: impl Fn(T) -> bool) -> Result<()> {
+            if values.iter().all(|v| valid(*v)) {
+                Ok(())
+            } else {
+                Err(invalid(format!("{} out of range", name)))
+            }
+        }
+
+        let signed = |max: i32| move |n: i32| n != 0 && (-max..=max).contains(&n);
+
+        if self.interval == 0 {
+            return Err(invalid("INTERVAL must be at least 1"));
+        }
+        if self.count.is_some() && self.until.is_some() {
+            return Err(invalid("COUNT and UNTIL must not be used together"));
+        }
+        if !matches!(self.freq, Frequency::Monthly | Frequency::Yearly)
+            && self.by_day.iter().any(|d| d.ordinal.is_some())
+        {
+            return Err(invalid(
+                "BYDAY ordinals are only allowed with FREQ=MONTHLY or FREQ=YEARLY",
+            ));
+        }
+        if self.freq != Frequency::Yearly && !self.by_week_no.is_empty() {
+            return Err(invalid("BYWEEKNO is only allowed with FREQ=YEARLY"));
+        }
+        if matches!(self.freq, Frequency::Daily | Frequency::Weekly | Frequency::Monthly)
+            && !self.by_year_day.is_empty()
+        {
+            return Err(invalid("BYYEARDAY is not allowed with FREQ=DAILY, WEEKLY or MONTHLY"));
+        }
+        if self.freq == Frequency::Weekly && !self.by_month_day.is_empty() {
+            return Err(invalid("BYMONTHDAY is not allowed with FREQ=WEEKLY"));
+        }
+
+        check(
+            &self.by_day.iter().filter_map(|d| d.ordinal).collect::<Vec<_>>(),
+            "BYDAY",
+            signed(53),
+        )?;
+        check(&self.by_month_day, "BYMONTHDAY", signed(31))?;
+        check(&self.by_year_day, "BYYEARDAY", signed(366))?;
+        check(&self.by_week_no, "BYWEEKNO", signed(53))?;
+        check(&self.by_hour, "BYHOUR", |h| h < 24)?;
+        check(&self.by_minute, "BYMINUTE", |m| m < 60)?;
+        check(&self.by_set_pos, "BYSETPOS", signed(366))
+    }
+
+    /// Generate the occurrences of the rule, starting at `start`
+    ///
+    /// An invalid rule yields its error once.
+    pub fn occurrences(&self, start: NaiveDateTime) -> RRuleIter {
+        RRuleIter::new(self, start)
+    }
+
+    // The rule with the values RFC 5545 derives from the start for missing parts
+    fn with_defaults(&self, start: &NaiveDateTime) -> RRule {
+        let mut rule = self.clone();
+        let start_day = Day::from(start.weekday());
+        let no_day_parts = rule.by_day.is_empty()
+            && rule.by_month_day.is_empty()
+            && rule.by_year_day.is_empty()
+            && rule.by_week_no.is_empty();
+
+        match rule.freq {
+            Frequency::Yearly if no_day_parts => {
+                if rule.by_month.is_empty() {
+                    rule.by_month = vec![Month::try_from(start.month()).expect("months are always valid")];
+                }
+                rule.by_month_day = vec![start.day() as i32];
+            },
+            Frequency::Yearly
+                if rule.by_day.is_empty() && rule.by_month_day.is_empty() && rule.by_year_day.is_empty() =>
+            {
+                rule.by_day = vec![ByDay::every(start_day)];
+            },
+            Frequency::Monthly if no_day_parts => rule.by_month_day = vec![start.day() as i32],
+            Frequency::Weekly if rule.by_day.is_empty() => rule.by_day = vec![ByDay::every(start_day)],
+            _ => {},
+        }
+
+        rule.by_hour.sort_unstable();
+        rule.by_hour.dedup();
+        rule.by_minute.sort_unstable();
+        rule.by_minute.dedup();
+        rule
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let days_in_month = days_in_month(date) as i32;
+        let days_in_year = days_in_year(date.year()) as i32;
+
+        (self.by_month.is_empty() || self.by_month.iter().any(|m| u32::from(*m) == date.month()))
+            && (self.by_week_no.is_empty() || {
+                let (week, weeks) = week_number(date, self.week_start);
+                self.by_week_no.iter().any(|n| matches_nth(*n, week, weeks))
+            })
+            && (self.by_year_day.is_empty()
+                || self
+                    .by_year_day
+                    .iter()
+                    .any(|n| matches_nth(*n, date.ordinal() as i32, days_in_year)))
+            && (self.by_month_day.is_empty()
+                || self
+                    .by_month_day
+                    .iter()
+                    .any(|n| matches_nth(*n, date.day() as i32, days_in_month)))
+            && (self.by_day.is_empty() || self.by_day.iter().any(|d| self.matches_by_day(d, date)))
+    }
+
+    fn matches_by_day(&self, by_day: &ByDay, date: NaiveDate) -> bool {
+        if Day::from(date.weekday()) != by_day.day {
+            return false;
+        }
+
+        match by_day.ordinal {
+            None => true,
+            Some(n) if self.freq == Frequency::Yearly && self.by_month.is_empty() => {
+                let (nth, total) = (date.ordinal0() as i32 / 7 + 1, days_in_year(date.year()) as i32);
+                matches_nth(n, nth, (total - date.ordinal0() as i32 - 1) / 7 + nth)
+            },
+            Some(n) => {
+                let (nth, total) = (date.day0() as i32 / 7 + 1, days_in_month(date) as i32);
+                matches_nth(n, nth, (total - date.day0() as i32 - 1) / 7 + nth)
+            },
+        }
+    }
+}
+
+/// Parses the RRULE text format, with or without a leading "RRULE:"
+///
+/// UNTIL values with a "Z" suffix are taken as they are, dates without a time include the whole
+/// day.
+impl FromStr for RRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<RRule> {
+        let offset = if s.len() >= 6 && s[..6].eq_ignore_ascii_case("RRULE:") {
+            6
+        } else {
+            0
+        };
+
+        let mut freq = None;
+        let mut rule = RRule::new(Frequency::Daily);
+        let mut position = offset;
+
+        for part in s[offset..].split(';') {
+            let part_position = position;
+            position += part.len() + 1;
+
+            if part.trim().is_empty() {
+                continue;
+            }
+
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| parse_error(part_position, "expected KEY=VALUE"))?;
+            let value_position = part_position + key.len() + 1;
+
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => freq = Some(parse_value(value, value_position, parse_frequency)?),
+                "INTERVAL" => rule.interval = parse_value(value, value_position, |v| v.parse().ok())?,
+                "COUNT" => rule.count = Some(parse_value(value, value_position, |v| v.parse().ok())?),
+                "UNTIL" => rule.until = Some(parse_value(value, value_position, parse_until)?),
+                "BYDAY" => rule.by_day = parse_list(value, value_position, parse_by_day)?,
+                "BYMONTHDAY" => rule.by_month_day = parse_list(value, value_position, |v| v.parse().ok())?,
+                "BYMONTH" => {
+                    rule.by_month = parse_list(value, value_position, |v| {
+                        v.parse::<u32>().ok().and_then(|n| Month::try_from(n).ok())
+                    })?
+                },
+                "BYYEARDAY" => rule.by_year_day = parse_list(value, value_position, |v| v.parse().ok())?,
+                "BYWEEKNO" => rule.by_week_no = parse_list(value, value_position, |v| v.parse().ok())?,
+                "BYHOUR" => rule.by_hour = parse_list(value, value_position, |v| v.parse().ok())?,
+                "BYMINUTE" => rule.by_minute = parse_list(value, value_position, |v| v.parse().ok())?,
+                "BYSETPOS" => rule.by_set_pos = parse_list(value, value_position, |v| v.parse().ok())?,
+                "WKST" => rule.week_start = parse_value(value, value_position, parse_day_code)?,
+                _ => return Err(parse_error(part_position, format!("unknown rule part '{}'", key))),
+            }
+        }
+
+        rule.freq = freq.ok_or_else(|| parse_error(offset, "missing FREQ"))?;
+        rule.validate().map(|_| rule)
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, name: &str, values: &[T]) -> fmt::Result {
+            if values.is_empty() {
+                return Ok(());
+            }
+
+            write!(f, ";{}=", name)?;
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}", v)?;
+            }
+            Ok(())
+        }
+
+        write!(f, "FREQ={}", self.freq)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%S"))?;
+        }
+
+        let months = self.by_month.iter().map(|m| u32::from(*m)).collect::<Vec<_>>();
+        list(f, "BYMONTH", &months)?;
+        list(f, "BYWEEKNO", &self.by_week_no)?;
+        list(f, "BYYEARDAY", &self.by_year_day)?;
+        list(f, "BYMONTHDAY", &self.by_month_day)?;
+        list(f, "BYDAY", &self.by_day)?;
+        list(f, "BYHOUR", &self.by_hour)?;
+        list(f, "BYMINUTE", &self.by_minute)?;
+        list(f, "BYSETPOS", &self.by_set_pos)?;
+
+        if self.week_start != Day::Monday {
+            write!(f, ";WKST={}", day_code(self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+/// The iterator over the occurrences of a `RRule`, see `RRule::occurrences()`
+///
+/// Rules without an end are endless. A rule which cannot produce any more occurrences ends the
+/// iteration after searching for 400 years.
+#[derive(Debug)]
+pub struct RRuleIter {
+    rule: RRule,
+    start: NaiveDateTime,
+    period: i64,
+    pending: VecDeque<NaiveDateTime>,
+    emitted: u32,
+    last_found: NaiveDate,
+    error: Option<Error>,
+    done: bool,
+}
+
+impl RRuleIter {
+    fn new(rule: &RRule, start: NaiveDateTime) -> RRuleIter {
+        let start = start.with_nanosecond(0).unwrap_or(start);
+
+        RRuleIter {
+            rule: rule.with_defaults(&start),
+            start,
+            period: 0,
+            pending: VecDeque::new(),
+            emitted: 0,
+            last_found: start.date(),
+            error: rule.validate().err(),
+            done: false,
+        }
+    }
+
+    // The days and times of the n-th period, or None if it is not representable
+    fn expand(&self, n: i64) -> Option<(NaiveDate, Vec<NaiveDateTime>)> {
+        let rule = &self.rule;
+        let start = self.start;
+        let steps = n.checked_mul(rule.interval as i64)?;
+
+        let (days, times) = match rule.freq {
+            Frequency::Yearly => {
+                let year = i32::try_from(start.year() as i64 + steps).ok()?;
+                let (first, end) = if rule.by_week_no.is_empty() {
+                    (
+                        NaiveDate::from_ymd_opt(year, 1, 1)?,
+                        NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+                    )
+                } else {
+                    // the days of the weeks numbered in `year`
+                    (week_one(year, rule.week_start)?, week_one(year + 1, rule.week_start)?)
+                };
+                (days_between(first, end), self.times_of_day())
+            },
+            Frequency::Monthly => {
+                let month = (start.year() as i64 * 12 + start.month0() as i64).checked_add(steps)?;
+                let year = i32::try_from(month.div_euclid(12)).ok()?;
+                let first = NaiveDate::from_ymd_opt(year, month.rem_euclid(12) as u32 + 1, 1)?;
+                let end = first.checked_add_signed(Duration::days(days_in_month(first) as i64))?;
+                (days_between(first, end), self.times_of_day())
+            },
+            Frequency::Weekly => {
+                let offset = (start.weekday().num_days_from_monday() + 7
+                    - chrono::Weekday::from(rule.week_start).num_days_from_monday())
+                    % 7;
+                let first = start
+                    .date()
+                    .checked_sub_signed(Duration::days(offset as i64))?
+                    .checked_add_signed(Duration::try_weeks(steps)?)?;
+                let end = first.checked_add_signed(Duration::days(7))?;
+                (days_between(first, end), self.times_of_day())
+            },
+            Frequency::Daily => {
+                let day = start.date().checked_add_signed(Duration::try_days(steps)?)?;
+                (vec![day], self.times_of_day())
+            },
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                let (base, step) = match rule.freq {
+                    Frequency::Hourly => (start.with_minute(0)?.with_second(0)?, Duration::try_hours(steps)?),
+                    Frequency::Minutely => (start.with_second(0)?, Duration::try_minutes(steps)?),
+                    _ => (start, Duration::try_seconds(steps)?),
+                };
+                let moment = base.checked_add_signed(step)?;
+                (vec![moment.date()], self.times_in(moment.time()))
+            },
+        };
+
+        let first = *days.first()?;
+        let mut set = days
+            .into_iter()
+            .filter(|d| rule.matches_day(*d))
+            .flat_map(|d| times.iter().map(move |t| d.and_time(*t)))
+            .collect::<Vec<_>>();
+
+        if !rule.by_set_pos.is_empty() {
+            let len = set.len() as i32;
+            let mut positions = rule
+                .by_set_pos
+                .iter()
+                .map(|p| if *p > 0 { p - 1 } else { len + p })
+                .filter(|i| (0..len).contains(i))
+                .collect::<Vec<_>>();
+            positions.sort_unstable();
+            positions.dedup();
+            set = positions.into_iter().map(|i| set[i as usize]).collect();
+        }
+
+        Some((first, set))
+    }
+
+    // For periods shorter than a day, the first period on the next day after `day` which matches
+    // the rule. None if there is none within the search limit.
+    fn first_period_after(&self, day: NaiveDate) -> Option<i64> {
+        let (base, unit) = match self.rule.freq {
+            Frequency::Hourly => (self.start.with_minute(0)?.with_second(0)?, 3600),
+            Frequency::Minutely => (self.start.with_second(0)?, 60),
+            Frequency::Secondly => (self.start, 1),
+            _ => return Some(self.period),
+        };
+
+        let mut next = day.succ_opt()?;
+        while !self.rule.matches_day(next) {
+            if next.year() - self.last_found.year() > MAX_YEARS_WITHOUT_OCCURRENCE {
+                return None;
+            }
+            next = next.succ_opt()?;
+        }
+
+        let length = (self.rule.interval as i64).checked_mul(unit)?;
+        let seconds = next.and_time(NaiveTime::MIN).signed_duration_since(base).num_seconds();
+        Some(seconds.div_euclid(length) + i64::from(seconds.rem_euclid(length) != 0))
+    }
+
+    // The times of each day for periods of a day or longer
+    fn times_of_day(&self) -> Vec<NaiveTime> {
+        let hours = if self.rule.by_hour.is_empty() {
+            vec![self.start.hour()]
+        } else {
+            self.rule.by_hour.clone()
+        };
+
+        hours
+            .into_iter()
+            .flat_map(|h| self.minutes().into_iter().map(move |m| (h, m)))
+            .filter_map(|(h, m)| NaiveTime::from_hms_opt(h, m, self.start.second()))
+            .collect()
+    }
+
+    // The times within a period shorter than a day, starting at `time`
+    fn times_in(&self, time: NaiveTime) -> Vec<NaiveTime> {
+        let rule = &self.rule;
+        if !rule.by_hour.is_empty() && !rule.by_hour.contains(&time.hour()) {
+            return Vec::new();
+        }
+
+        match rule.freq {
+            Frequency::Hourly => self
+                .minutes()
+                .into_iter()
+                .filter_map(|m| NaiveTime::from_hms_opt(time.hour(), m, self.start.second()))
+                .collect(),
+            _ if !rule.by_minute.is_empty() && !rule.by_minute.contains(&time.minute()) => Vec::new(),
+            Frequency::Minutely => NaiveTime::from_hms_opt(time.hour(), time.minute(), self.start.second())
+                .into_iter()
+                .collect(),
+            _ => vec![time],
+        }
+    }
+
+    fn minutes(&self) -> Vec<u32> {
+        if self.rule.by_minute.is_empty() {
+            vec![self.start.minute()]
+        } else {
+            self.rule.by_minute.clone()
+        }
+    }
+}
+
+impl Iterator for RRuleIter {
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+
+        while !self.done {
+            if let Some(moment) = self.pending.pop_front() {
+                if self.rule.until.is_some_and(|until| moment > until)
+                    || self.rule.count.is_some_and(|count| self.emitted >= count)
+                {
+                    self.done = true;
+                    return None;
+                }
+
+                self.emitted += 1;
+                return Some(Ok(TimeType::moment(moment)));
+            }
+
+            match self.expand(self.period) {
+                None => self.done = true,
+                Some((first, set)) => {
+                    self.period += 1;
+                    self.pending.extend(set.into_iter().filter(|m| *m >= self.start));
+
+                    if let Some(last) = self.pending.back() {
+                        self.last_found = last.date();
+                    } else if first.year() - self.last_found.year() > MAX_YEARS_WITHOUT_OCCURRENCE {
+                        self.done = true;
+                    } else if !self.rule.matches_day(first) {
+                        // no other period of this day can match, skip the day as a whole
+                        match self.first_period_after(first) {
+                            Some(period) => self.period = self.period.max(period),
+                            None => self.done = true,
+                        }
+                    }
+                },
+            }
+        }
+
+        None
+    }
+}
+
+/// Several rules and dates, minus exclusions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecurrenceSet {
+    rules: Vec<RRule>,
+    dates: Vec<NaiveDateTime>,
+    exclusion_rules: Vec<RRule>,
+    exclusion_dates: Vec<NaiveDateTime>,
+}
+
+impl RecurrenceSet {
+    pub fn new() -> RecurrenceSet {
+        RecurrenceSet::default()
+    }
+
+    /// Include the occurrences of `rule` (RRULE)
+    pub fn with_rule(mut self, rule: RRule) -> RecurrenceSet {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Include a single moment (RDATE)
+    pub fn with_date(mut self, date: NaiveDateTime) -> RecurrenceSet {
+        self.dates.push(date);
+        self
+    }
+
+    /// Exclude the occurrences of `rule` (EXRULE)
+    pub fn with_exclusion_rule(mut self, rule: RRule) -> RecurrenceSet {
+        self.exclusion_rules.push(rule);
+        self
+    }
+
+    /// Exclude a single moment (EXDATE)
+    pub fn with_exclusion_date(mut self, date: NaiveDateTime) -> RecurrenceSet {
+        self.exclusion_dates.push(date);
+        self
+    }
+
+    pub fn rules(&self) -> &[RRule] {
+        &self.rules
+    }
+
+    pub fn dates(&self) -> &[NaiveDateTime] {
+        &self.dates
+    }
+
+    pub fn exclusion_rules(&self) -> &[RRule] {
+        &self.exclusion_rules
+    }
+
+    pub fn exclusion_dates(&self) -> &[NaiveDateTime] {
+        &self.exclusion_dates
+    }
+
+    /// Generate the occurrences of the set, in order and without duplicates
+    ///
+    /// The rules, including the exclusion rules, start at `start`. Extra dates are included even
+    /// if they lie before `start`.
+    pub fn occurrences(&self, start: NaiveDateTime) -> RecurrenceSetIter {
+        let streams = |rules: &[RRule]| {
+            rules
+                .iter()
+                .map(|rule| RuleStream {
+                    iter: rule.occurrences(start),
+                    head: None,
+                })
+                .collect()
+        };
+
+        let mut dates = self.dates.clone();
+        dates.sort_unstable();
+        dates.dedup();
+
+        RecurrenceSetIter {
+            rules: streams(&self.rules),
+            dates: dates.into(),
+            exclusion_rules: streams(&self.exclusion_rules),
+            exclusion_dates: self.exclusion_dates.iter().copied().collect(),
+            started: false,
+            done: false,
+        }
+    }
+}
+
+// A rule iterator and its next occurrence
+#[derive(Debug)]
+struct RuleStream {
+    iter: RRuleIter,
+    head: Option<NaiveDateTime>,
+}
+
+impl RuleStream {
+    fn advance(&mut self) -> Result<()> {
+        self.head = match self.iter.next() {
+            None => None,
+            Some(tt) => tt?.get_moment().copied(),
+        };
+        Ok(())
+    }
+
+    // Advance to the first occurrence at or after `moment` and check whether it is `moment`
+    fn reaches(&mut self, moment: &NaiveDateTime) -> Result<bool> {
+        while self.head.is_some_and(|head| head < *moment) {
+            self.advance()?;
+        }
+        Ok(self.head.as_ref() == Some(moment))
+    }
+}
+
+/// The iterator over the occurrences of a `RecurrenceSet`, see `RecurrenceSet::occurrences()`
+///
+/// # Warning
+///
+/// If an exclusion rule excludes all occurrences of an endless rule, `next()` does not return.
+#[derive(Debug)]
+pub struct RecurrenceSetIter {
+    rules: Vec<RuleStream>,
+    dates: VecDeque<NaiveDateTime>,
+    exclusion_rules: Vec<RuleStream>,
+    exclusion_dates: BTreeSet<NaiveDateTime>,
+    started: bool,
+    done: bool,
+}
+
+impl RecurrenceSetIter {
+    fn next_moment(&mut self) -> Result<Option<NaiveDateTime>> {
+        if !self.started {
+            self.started = true;
+            for stream in self.rules.iter_mut().chain(self.exclusion_rules.iter_mut()) {
+                stream.advance()?;
+            }
+        }
+
+        loop {
+            let candidate = self
+                .rules
+                .iter()
+                .filter_map(|stream| stream.head)
+                .chain(self.dates.front().copied())
+                .min();
+
+            let candidate = match candidate {
+                None => return Ok(None),
+                Some(c) => c,
+            };
+
+            for stream in self.rules.iter_mut().filter(|stream| stream.head == Some(candidate)) {
+                stream.advance()?;
+            }
+            if self.dates.front() == Some(&candidate) {
+                self.dates.pop_front();
+            }
+
+            if self.exclusion_dates.contains(&candidate) {
+                continue;
+            }
+
+            let mut excluded = false;
+            for stream in self.exclusion_rules.iter_mut() {
+                excluded |= stream.reaches(&candidate)?;
+            }
+
+            if !excluded {
+                return Ok(Some(candidate));
+            }
+        }
+    }
+}
+
+impl Iterator for RecurrenceSetIter {
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_moment() {
+            Ok(Some(moment)) => Some(Ok(TimeType::moment(moment))),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+fn invalid<S: Into<String>>(message: S) -> Error {
+    Error::InvalidRecurrence(message.into())
+}
+
+fn parse_value<T>(value: &str, position: usize, parse: impl Fn(&str) -> Option<T>) -> Result<T> {
+    parse(value.trim()).ok_or_else(|| parse_error(position, format!("invalid value '{}'", value)))
+}
+
+fn parse_list<T>(value: &str, position: usize, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>> {
+    let mut position = position;
+    value
+        .split(',')
+        .map(|item| {
+            let result = parse_value(item, position, &parse);
+            position += item.len() + 1;
+            result
+        })
+        .collect()
+}
+
+fn parse_frequency(s: &str) -> Option<Frequency> {
+    [
+        Frequency::Secondly,
+        Frequency::Minutely,
+        Frequency::Hourly,
+        Frequency::Daily,
+        Frequency::Weekly,
+        Frequency::Monthly,
+        Frequency::Yearly,
+    ]
+    .into_iter()
+    .find(|f| f.name().eq_ignore_ascii_case(s))
+}
+
+fn parse_day_code(s: &str) -> Option<Day> {
+    DAY_CODES
+        .iter()
+        .position(|code| code.eq_ignore_ascii_case(s))
+        .map(|i| Day::all()[i])
+}
+
+fn parse_by_day(s: &str) -> Option<ByDay> {
+    let split = s.len().checked_sub(2)?;
+    let day = parse_day_code(s.get(split..)?)?;
+    match s.get(..split)? {
+        "" => Some(ByDay::every(day)),
+        ordinal => ordinal.trim_start_matches('+').parse().ok().map(|n| ByDay::nth(n, day)),
+    }
+}
+
+fn parse_until(s: &str) -> Option<NaiveDateTime> {
+    let s = s.strip_suffix(['Z', 'z']).unwrap_or(s);
+    NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").ok().or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y%m%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(23, 59, 59))
+    })
+}
+
+fn day_code(day: Day) -> &'static str {
+    DAY_CODES[chrono::Weekday::from(day).num_days_from_monday() as usize]
+}
+
+fn days_in_year(year: i32) -> u32 {
+    Year(year).num_days()
+}
+
+fn days_between(first: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    first.iter_days().take_while(|d| *d < end).collect()
+}
+
+// Whether `n` (counted from 1, or from the end if negative) refers to position `pos` of `total`
+fn matches_nth(n: i32, pos: i32, total: i32) -> bool {
+    if n > 0 {
+        n == pos
+    } else {
+        total + n + 1 == pos
+    }
+}
+
+// The first day of week 1 of `year`, which is the first week with at least four days in `year`
+fn week_one(year: i32, week_start: Day) -> Option<NaiveDate> {
+    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let offset =
+        (jan1.weekday().num_days_from_monday() + 7 - chrono::Weekday::from(week_start).num_days_from_monday()) % 7;
+
+    if offset <= 3 {
+        jan1.checked_sub_signed(Duration::days(offset as i64))
+    } else {
+        jan1.checked_add_signed(Duration::days(7 - offset as i64))
+    }
+}
+
+// The week number of `date` and the number of weeks in its week-numbering year
+fn week_number(date: NaiveDate, week_start: Day) -> (i32, i32) {
+    let week_one = |y| week_one(y, week_start).unwrap_or(NaiveDate::MIN);
+    let year = date.year();
+    let year = if date >= week_one(year + 1) {
+        year + 1
+    } else if date < week_one(year) {
+        year - 1
+    } else {
+        year
+    };
+
+    let first = week_one(year);
+    let week = (date - first).num_days() / 7 + 1;
+    let weeks = (week_one(year + 1) - first).num_days() / 7;
+    (week as i32, weeks as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn occurrences(rule: &str, start: NaiveDateTime, n: usize) -> Vec<NaiveDateTime> {
+        rule.parse::<RRule>()
+            .unwrap()
+            .occurrences(start)
+            .take(n)
+            .map(|tt| *tt.unwrap().get_moment().unwrap())
+            .collect()
+    }
+
+    fn dates(v: Vec<NaiveDateTime>) -> Vec<(i32, u32, u32)> {
+        v.into_iter().map(|m| (m.year(), m.month(), m.day())).collect()
+    }
+
+    #[test]
+    fn test_last_weekday_of_month() {
+        let v = occurrences(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            ymd_hms(2024, 1, 1, 9, 0, 0),
+            6,
+        );
+
+        assert_eq!(
+            dates(v.clone()),
+            vec![
+                (2024, 1, 31),
+                (2024, 2, 29),
+                (2024, 3, 29),
+                (2024, 4, 30),
+                (2024, 5, 31),
+                (2024, 6, 28)
+            ]
+        );
+        assert!(v.iter().all(|m| m.hour() == 9));
+    }
+
+    #[test]
+    fn test_daily_count() {
+        let v = occurrences("FREQ=DAILY;COUNT=3", ymd_hms(1997, 9, 2, 9, 0, 0), 10);
+        assert_eq!(dates(v), vec![(1997, 9, 2), (1997, 9, 3), (1997, 9, 4)]);
+    }
+
+    #[test]
+    fn test_weekly_until() {
+        // every other week on Tuesday and Thursday, until 1997-10-07 (RFC 5545)
+        let v = occurrences(
+            "RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=19971007T000000Z;WKST=SU;BYDAY=TU,TH",
+            ymd_hms(1997, 9, 2, 9, 0, 0),
+            100,
+        );
+
+        assert_eq!(
+            dates(v),
+            vec![
+                (1997, 9, 2),
+                (1997, 9, 4),
+                (1997, 9, 16),
+                (1997, 9, 18),
+                (1997, 9, 30),
+                (1997, 10, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_week_start() {
+        // RFC 5545: changing only WKST changes the result
+        let start = ymd_hms(1997, 8, 5, 9, 0, 0);
+        let monday = occurrences("FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO", start, 10);
+        let sunday = occurrences("FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=SU", start, 10);
+
+        assert_eq!(
+            dates(monday),
+            vec![(1997, 8, 5), (1997, 8, 10), (1997, 8, 19), (1997, 8, 24)]
+        );
+        assert_eq!(
+            dates(sunday),
+            vec![(1997, 8, 5), (1997, 8, 17), (1997, 8, 19), (1997, 8, 31)]
+        );
+    }
+
+    #[test]
+    fn test_by_day_ordinals() {
+        let v = occurrences("FREQ=MONTHLY;BYDAY=-1FR", ymd_hms(2024, 1, 1, 0, 0, 0), 3);
+        assert_eq!(dates(v), vec![(2024, 1, 26), (2024, 2, 23), (2024, 3, 29)]);
+
+        let v = occurrences("FREQ=MONTHLY;BYDAY=1FR,-2MO", ymd_hms(2024, 1, 1, 0, 0, 0), 4);
+        assert_eq!(dates(v), vec![(2024, 1, 5), (2024, 1, 22), (2024, 2, 2), (2024, 2, 19)]);
+
+        // the 20th Monday of the year (RFC 5545)
+        let v = occurrences("FREQ=YEARLY;BYDAY=20MO", ymd_hms(1997, 5, 19, 9, 0, 0), 3);
+        assert_eq!(dates(v), vec![(1997, 5, 19), (1998, 5, 18), (1999, 5, 17)]);
+
+        // Thanksgiving
+        let v = occurrences("FREQ=YEARLY;BYMONTH=11;BYDAY=+4TH", ymd_hms(2023, 1, 1, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2023, 11, 23), (2024, 11, 28)]);
+    }
+
+    #[test]
+    fn test_by_week_no() {
+        // Monday of week 20 (RFC 5545)
+        let v = occurrences("FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO", ymd_hms(1997, 5, 12, 9, 0, 0), 3);
+        assert_eq!(dates(v), vec![(1997, 5, 12), (1998, 5, 11), (1999, 5, 17)]);
+
+        // week 1 of 2025 starts in 2024
+        let v = occurrences("FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO", ymd_hms(2024, 6, 1, 0, 0, 0), 1);
+        assert_eq!(dates(v), vec![(2024, 12, 30)]);
+
+        let v = occurrences("FREQ=YEARLY;BYWEEKNO=-1;BYDAY=TH", ymd_hms(2020, 1, 1, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2020, 12, 31), (2021, 12, 30)]);
+    }
+
+    #[test]
+    fn test_by_year_day() {
+        let v = occurrences(
+            "FREQ=YEARLY;COUNT=4;BYYEARDAY=1,100,200",
+            ymd_hms(1997, 1, 1, 9, 0, 0),
+            10,
+        );
+        assert_eq!(dates(v), vec![(1997, 1, 1), (1997, 4, 10), (1997, 7, 19), (1998, 1, 1)]);
+
+        let v = occurrences("FREQ=YEARLY;BYYEARDAY=-1", ymd_hms(2023, 1, 1, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2023, 12, 31), (2024, 12, 31)]);
+    }
+
+    #[test]
+    fn test_by_month_day() {
+        let v = occurrences("FREQ=MONTHLY;BYMONTHDAY=-3", ymd_hms(2024, 1, 1, 0, 0, 0), 3);
+        assert_eq!(dates(v), vec![(2024, 1, 29), (2024, 2, 27), (2024, 3, 29)]);
+
+        // months without a 31st are skipped
+        let v = occurrences("FREQ=MONTHLY;COUNT=3", ymd_hms(2024, 1, 31, 0, 0, 0), 10);
+        assert_eq!(dates(v), vec![(2024, 1, 31), (2024, 3, 31), (2024, 5, 31)]);
+
+        // Friday the 13th
+        let v = occurrences("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13", ymd_hms(2024, 1, 1, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2024, 9, 13), (2024, 12, 13)]);
+    }
+
+    #[test]
+    fn test_yearly_defaults() {
+        let v = occurrences("FREQ=YEARLY", ymd_hms(2024, 2, 29, 0, 0, 0), 2);
+        assert_eq!(dates(v), vec![(2024, 2, 29), (2028, 2, 29)]);
+
+        let v = occurrences("FREQ=YEARLY;BYMONTH=1,7", ymd_hms(2024, 3, 5, 0, 0, 0), 3);
+        assert_eq!(dates(v), vec![(2024, 7, 5), (2025, 1, 5), (2025, 7, 5)]);
+    }
+
+    #[test]
+    fn test_sub_daily() {
+        let v = occurrences(
+            "FREQ=HOURLY;INTERVAL=3;UNTIL=19970902T170000",
+            ymd_hms(1997, 9, 2, 9, 0, 0),
+            10,
+        );
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(1997, 9, 2, 9, 0, 0),
+                ymd_hms(1997, 9, 2, 12, 0, 0),
+                ymd_hms(1997, 9, 2, 15, 0, 0)
+            ]
+        );
+
+        // RFC 5545: both rules give every 20 minutes between 9:00 and 10:40
+        let start = ymd_hms(1997, 9, 2, 9, 0, 0);
+        let minutely = occurrences("FREQ=MINUTELY;INTERVAL=20;BYHOUR=9,10", start, 7);
+        let daily = occurrences("FREQ=DAILY;BYHOUR=9,10;BYMINUTE=0,20,40", start, 7);
+        assert_eq!(minutely, daily);
+        assert_eq!(minutely[5], ymd_hms(1997, 9, 2, 10, 40, 0));
+        assert_eq!(minutely[6], ymd_hms(1997, 9, 3, 9, 0, 0));
+
+        let v = occurrences("FREQ=SECONDLY;INTERVAL=30;BYMINUTE=1", ymd_hms(2024, 1, 1, 0, 0, 0), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 0, 1, 0),
+                ymd_hms(2024, 1, 1, 0, 1, 30),
+                ymd_hms(2024, 1, 1, 1, 1, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_start_is_only_yielded_if_it_matches() {
+        let v = occurrences("FREQ=WEEKLY;BYDAY=FR", ymd_hms(2024, 1, 1, 8, 0, 0), 1);
+        assert_eq!(v, vec![ymd_hms(2024, 1, 5, 8, 0, 0)]);
+    }
+
+    #[test]
+    fn test_impossible_rule_ends() {
+        let v = occurrences("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", ymd_hms(2024, 1, 1, 0, 0, 0), 1);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_impossible_rule_ends_quickly_for_short_periods() {
+        let begin = std::time::Instant::now();
+        for freq in ["HOURLY", "MINUTELY", "SECONDLY"] {
+            let rule = format!("FREQ={};BYMONTH=2;BYMONTHDAY=30", freq);
+            assert!(occurrences(&rule, ymd_hms(2024, 1, 1, 0, 0, 0), 1).is_empty());
+        }
+        assert!(begin.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_short_periods_skip_to_matching_days() {
+        let v = occurrences("FREQ=HOURLY;INTERVAL=5;BYMONTHDAY=3", ymd_hms(2024, 1, 1, 1, 30, 0), 3);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 3, 3, 30, 0),
+                ymd_hms(2024, 1, 3, 8, 30, 0),
+                ymd_hms(2024, 1, 3, 13, 30, 0),
+            ]
+        );
+
+        let v = occurrences("FREQ=MINUTELY;INTERVAL=7;BYDAY=SU", ymd_hms(2024, 1, 1, 0, 0, 0), 1);
+        // 2024-01-07 00:00 is 6 * 1440 minutes after the start, the next multiple of 7 is 8645
+        assert_eq!(v, vec![ymd_hms(2024, 1, 7, 0, 5, 0)]);
+    }
+
+    #[test]
+    fn test_minutely_keeps_the_seconds_of_the_start() {
+        let v = occurrences("FREQ=MINUTELY;COUNT=3", ymd_hms(2024, 1, 1, 9, 0, 30), 5);
+        assert_eq!(
+            v,
+            vec![
+                ymd_hms(2024, 1, 1, 9, 0, 30),
+                ymd_hms(2024, 1, 1, 9, 1, 30),
+                ymd_hms(2024, 1, 1, 9, 2, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_periods_at_the_end_of_the_date_range_end_the_iteration() {
+        let start = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap() - Duration::days(11);
+        for freq in ["MONTHLY", "WEEKLY"] {
+            let v = occurrences(&format!("FREQ={}", freq), start, 5);
+            assert!(v.len() < 5);
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        let rule = RRule::new(Frequency::Monthly)
+            .with_by_day(vec![ByDay::nth(-1, Day::Friday)])
+            .with_count(2);
+        let v = rule
+            .occurrences(ymd_hms(2024, 1, 1, 0, 0, 0))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            v,
+            vec![
+                TimeType::moment(ymd_hms(2024, 1, 26, 0, 0, 0)),
+                TimeType::moment(ymd_hms(2024, 2, 23, 0, 0, 0))
+            ]
+        );
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;COUNT=2;BYDAY=-1FR");
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for s in [
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            "FREQ=WEEKLY;INTERVAL=2;UNTIL=19971007T000000;BYDAY=TU,TH;WKST=SU",
+            "FREQ=YEARLY;COUNT=4;BYMONTH=1,2;BYWEEKNO=20;BYYEARDAY=1,-1;BYMONTHDAY=-3;BYDAY=1MO;BYHOUR=9;BYMINUTE=0,30",
+        ] {
+            let rule = s.parse::<RRule>().unwrap();
+            assert_eq!(rule.to_string(), s);
+            assert_eq!(rule.to_string().parse::<RRule>().unwrap(), rule);
+        }
+
+        let rule = "rrule:freq=daily;until=20240101".parse::<RRule>().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=DAILY;UNTIL=20240101T235959");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let position = |s: &str| s.parse::<RRule>().unwrap_err().position();
+
+        assert_eq!(position("COUNT=3"), Some(0));
+        assert_eq!(position("FREQ=DAILY;FOO=1"), Some(11));
+        assert_eq!(position("FREQ=DAILY;COUNT"), Some(11));
+        assert_eq!(position("FREQ=DAILY;BYHOUR=1,x"), Some(20));
+        assert_eq!(position("RRULE:FREQ=DAILY;BYDAY=XY"), Some(23));
+        assert_eq!(position("FREQ=MONTHLY;BYMONTH=13"), Some(21));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        for s in [
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=1;UNTIL=20240101",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYWEEKNO=1",
+            "FREQ=MONTHLY;BYYEARDAY=1",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=DAILY;BYHOUR=24",
+            "FREQ=MONTHLY;BYSETPOS=0",
+        ] {
+            let e = s.parse::<RRule>().unwrap_err();
+            assert_eq!(e.code(), "argument/invalid-recurrence", "{}", s);
+        }
+
+        let v = RRule::new(Frequency::Daily)
+            .with_interval(0)
+            .occurrences(ymd_hms(2024, 1, 1, 0, 0, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(v.len(), 1);
+        assert!(v[0].is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_recurrence_set {
+    use crate::util::tests::ymd_hms;
+
+    use super::*;
+
+    fn collect(iter: RecurrenceSetIter) -> Vec<NaiveDateTime> {
+        iter.map(|tt| *tt.unwrap().get_moment().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_weekly_meeting_with_cancelled_instance() {
+        let set = RecurrenceSet::new()
+            .with_rule("FREQ=WEEKLY;BYDAY=TU;COUNT=4".parse().unwrap())
+            .with_exclusion_date(ymd_hms(2024, 1, 16, 10, 0, 0));
+
+        assert_eq!(
+            collect(set.occurrences(ymd_hms(2024, 1, 2, 10, 0, 0))),
+            vec![
+                ymd_hms(2024, 1, 2, 10, 0, 0),
+                ymd_hms(2024, 1, 9, 10, 0, 0),
+                ymd_hms(2024, 1, 23, 10, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_rules_and_dates() {
+        let set = RecurrenceSet::new()
+            .with_rule("FREQ=WEEKLY;BYDAY=MO;COUNT=3".parse().unwrap())
+            .with_rule("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=3".parse().unwrap())
+            .with_date(ymd_hms(2024, 1, 5, 0, 0, 0))
+            .with_date(ymd_hms(2024, 1, 1, 0, 0, 0))
+            .with_date(ymd_hms(2023, 12, 31, 0, 0, 0));
+
+        assert_eq!(
+            collect(set.occurrences(ymd_hms(2024, 1, 1, 0, 0, 0))),
+            vec![
+                ymd_hms(2023, 12, 31, 0, 0, 0),
+                ymd_hms(2024, 1, 1, 0, 0, 0),
+                ymd_hms(2024, 1, 3, 0, 0, 0),
+                ymd_hms(2024, 1, 5, 0, 0, 0),
+                ymd_hms(2024, 1, 8, 0, 0, 0),
+                ymd_hms(2024, 1, 15, 0, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exclusion_rule() {
+        // every day in January, except on weekends
+        let set = RecurrenceSet::new()
+            .with_rule("FREQ=DAILY;UNTIL=20240131".parse().unwrap())
+            .with_exclusion_rule("FREQ=WEEKLY;BYDAY=SA,SU".parse().unwrap());
+
+        let v = collect(set.occurrences(ymd_hms(2024, 1, 1, 0, 0, 0)));
+        assert_eq!(v.len(), 23);
+        assert!(v.iter().all(|m| m.weekday().num_days_from_monday() < 5));
+    }
+
+    #[test]
+    fn test_empty_set() {
+        let v = collect(RecurrenceSet::new().occurrences(ymd_hms(2024, 1, 1, 0, 0, 0)));
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_passed_on() {
+        let set = RecurrenceSet::new()
+            .with_rule(RRule::new(Frequency::Daily))
+            .with_exclusion_rule(RRule::new(Frequency::Daily).with_interval(0));
+
+        let v = set.occurrences(ymd_hms(2024, 1, 1, 0, 0, 0)).collect::<Vec<_>>();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].as_ref().unwrap_err().code(), "argument/invalid-recurrence");
+    }
+
+    #[test]
+    fn test_filter_adapters() {
+        use crate::indicator::Day;
+        use crate::iter::WithoutFilter;
+
+        let set = RecurrenceSet::new().with_rule("FREQ=DAILY;COUNT=7".parse().unwrap());
+        let v = set
+            .occurrences(ymd_hms(2024, 1, 1, 0, 0, 0))
+            .without(Day::Monday)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(v.len(), 6);
+    }
+}
@@ -1,6 +1,7 @@
 //! The module for the TimeType
 //!
 
+use std::fmt;
 use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::Sub;
@@ -9,14 +10,28 @@ use std::ops::SubAssign;
 use chrono::Datelike;
 use chrono::NaiveDate;
 use chrono::NaiveDateTime;
+use chrono::NaiveTime;
 use chrono::Timelike;
 
+use crate::difference::Difference;
+use crate::difference::Unit;
 use crate::error::Error;
+use crate::error::Operation;
 use crate::error::Result;
-use crate::indicator::{Day, Month};
+use crate::explain::Explanation;
+use crate::explain::Rule;
+use crate::explain::Trace;
+use crate::fiscal::FiscalCalendar;
+use crate::indicator::{Day, Mark, Month};
+use crate::iter::IntoIncrement;
+use crate::iter::Occurrences;
+use crate::matcher::Matcher;
 use crate::util::*;
 
 /// A Type of Time, currently based on chrono::NaiveDateTime
+///
+/// Besides amounts and full moments, a TimeType can be a date without a time (like an all-day
+/// event) or a time of day without a date. A date plus a time of day is a moment.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TimeType {
     Seconds(i64),
@@ -27,6 +42,8 @@ pub enum TimeType {
     Years(i64),
 
     Moment(NaiveDateTime),
+    Date(NaiveDate),
+    Time(NaiveTime),
 
     Addition(Box<TimeType>, Box<TimeType>),
     Subtraction(Box<TimeType>, Box<TimeType>),
@@ -66,6 +83,50 @@ impl SubAssign for TimeType {
     }
 }
 
+/// Formats the TimeType as an expression, like "2024-01-31 00:00:00 + (1 month - 1 day)"
+impl fmt::Display for TimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::TimeType as TT;
+
+        fn amount(f: &mut fmt::Formatter<'_>, n: i64, unit: &str) -> fmt::Result {
+            write!(f, "{} {}{}", n, unit, if n == 1 { "" } else { "s" })
+        }
+
+        fn operand(f: &mut fmt::Formatter<'_>, tt: &TimeType) -> fmt::Result {
+            if tt.is_addition() || tt.is_subtraction() {
+                write!(f, "({})", tt)
+            } else {
+                write!(f, "{}", tt)
+            }
+        }
+
+        match self {
+            TT::Seconds(n) => amount(f, *n, "second"),
+            TT::Minutes(n) => amount(f, *n, "minute"),
+            TT::Hours(n) => amount(f, *n, "hour"),
+            TT::Days(n) => amount(f, *n, "day"),
+            TT::Months(n) => amount(f, *n, "month"),
+            TT::Years(n) => amount(f, *n, "year"),
+            TT::Moment(m) => write!(f, "{}", m),
+            TT::Date(d) => write!(f, "{}", d),
+            TT::Time(t) => write!(f, "{}", t),
+            TT::Addition(a, b) => {
+                write!(f, "{} + ", a)?;
+                operand(f, b)
+            },
+            TT::Subtraction(a, b) => {
+                write!(f, "{} - ", a)?;
+                operand(f, b)
+            },
+            TT::EndOfYear(e) => write!(f, "end-of-year({})", e),
+            TT::EndOfMonth(e) => write!(f, "end-of-month({})", e),
+            TT::EndOfDay(e) => write!(f, "end-of-day({})", e),
+            TT::EndOfHour(e) => write!(f, "end-of-hour({})", e),
+            TT::EndOfMinute(e) => write!(f, "end-of-minute({})", e),
+        }
+    }
+}
+
 /// The TimeType type
 ///
 /// # Warning
@@ -112,6 +173,14 @@ impl TimeType {
         matches!(self, TimeType::Moment(_))
     }
 
+    pub fn is_date(&self) -> bool {
+        matches!(self, TimeType::Date(_))
+    }
+
+    pub fn is_time(&self) -> bool {
+        matches!(self, TimeType::Time(_))
+    }
+
     pub fn is_addition(&self) -> bool {
         matches!(self, TimeType::Addition(_, _))
     }
@@ -153,14 +222,64 @@ impl TimeType {
         TimeType::Moment(ndt)
     }
 
+    /// A date without a time, like an all-day event
+    ///
+    /// Whole days, months and years can be added to and subtracted from a date. Adding a time of
+    /// day results in a `TimeType::Moment`.
+    pub fn date(nd: NaiveDate) -> TimeType {
+        TimeType::Date(nd)
+    }
+
+    /// A time of day without a date, like "at 14:30"
+    ///
+    /// Seconds, minutes and hours can be added to and subtracted from a time of day, wrapping
+    /// around midnight. Adding it to a date results in a `TimeType::Moment`.
+    pub fn time(nt: NaiveTime) -> TimeType {
+        TimeType::Time(nt)
+    }
+
+    /// Create a `TimeType::Moment` from a unix timestamp (seconds since 1970-01-01T00:00:00)
+    ///
+    /// The timestamp is interpreted as UTC. Returns an error if the timestamp is out of the range
+    /// chrono can represent.
+    pub fn from_timestamp(secs: i64) -> Result<TimeType> {
+        chrono::DateTime::from_timestamp(secs, 0)
+            .map(|dt| TimeType::moment(dt.naive_utc()))
+            .ok_or(Error::TimestampOutOfRange(secs))
+    }
+
+    /// Create a `TimeType::Moment` from a unix timestamp in milliseconds
+    ///
+    /// The timestamp is interpreted as UTC. Returns an error if the timestamp is out of the range
+    /// chrono can represent.
+    pub fn from_timestamp_millis(millis: i64) -> Result<TimeType> {
+        chrono::DateTime::from_timestamp_millis(millis)
+            .map(|dt| TimeType::moment(dt.naive_utc()))
+            .ok_or(Error::TimestampOutOfRange(millis))
+    }
+
+    /// Get the unix timestamp (seconds since 1970-01-01T00:00:00) of the current TimeType
+    ///
+    /// The TimeType is calculated and interpreted as UTC.
+    ///
+    /// # Warning
+    ///
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, this fails
+    pub fn to_timestamp(&self) -> Result<i64> {
+        let tt = self.clone().calculate()?;
+        tt.get_moment()
+            .map(|m| m.and_utc().timestamp())
+            .ok_or_else(|| Error::NotAMoment(tt.clone()))
+    }
+
     /// Calculate the end of the year based on the current TimeType
     ///
     /// The end of a year is considered to be the last day of the year, not the last second.
     ///
     /// # Warning
     ///
-    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
-    /// the year will fail
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment` or a `TimeType::Date`,
+    /// calculating the end of the year will fail
     ///
     pub fn end_of_year(self) -> TimeType {
         TimeType::EndOfYear(Box::new(self))
@@ -172,8 +291,8 @@ impl TimeType {
     ///
     /// # Warning
     ///
-    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
-    /// the month will fail
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment` or a `TimeType::Date`,
+    /// calculating the end of the month will fail
     pub fn end_of_month(self) -> TimeType {
         TimeType::EndOfMonth(Box::new(self))
     }
@@ -184,8 +303,8 @@ impl TimeType {
     ///
     /// # Warning
     ///
-    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
-    /// the day will fail
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment` or a `TimeType::Time`,
+    /// calculating the end of the day will fail
     pub fn end_of_day(self) -> TimeType {
         TimeType::EndOfDay(Box::new(self))
     }
@@ -196,8 +315,8 @@ impl TimeType {
     ///
     /// # Warning
     ///
-    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
-    /// the hour will fail
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment` or a `TimeType::Time`,
+    /// calculating the end of the hour will fail
     pub fn end_of_hour(self) -> TimeType {
         TimeType::EndOfHour(Box::new(self))
     }
@@ -208,8 +327,8 @@ impl TimeType {
     ///
     /// # Warning
     ///
-    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
-    /// the minute will fail
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment` or a `TimeType::Time`,
+    /// calculating the end of the minute will fail
     pub fn end_of_minute(self) -> TimeType {
         TimeType::EndOfMinute(Box::new(self))
     }
@@ -377,28 +496,132 @@ impl TimeType {
         }
     }
 
-    /// Check whether a `TimeType::Moment` is a certain weekday. Returns an error if TimeType is
-    /// not a `TimeType::Moment`.
-    pub fn is_a(&self, d: Day) -> Result<bool> {
-        use self::TimeType as TT;
+    pub fn get_date(&self) -> Option<&NaiveDate> {
+        match self {
+            TimeType::Date(d) => Some(d),
+            _ => None,
+        }
+    }
 
-        match *self {
-            TT::Moment(m) => Ok(m.weekday() == d.into()),
-            _ => Err(Error::CannotCompareDayTo(self.name())),
+    pub fn get_time(&self) -> Option<&NaiveTime> {
+        match self {
+            TimeType::Time(t) => Some(t),
+            _ => None,
         }
     }
 
-    /// Check whether a `TimeType::Moment` is in a certain month. Returns an error if the TimeType
-    /// is not a `TimeType::Moment`.
-    pub fn is_in(&self, month: Month) -> Result<bool> {
-        use self::TimeType as TT;
+    /// The date of a `TimeType::Moment` or `TimeType::Date`
+    pub(crate) fn get_calendar_date(&self) -> Option<NaiveDate> {
+        match self {
+            TimeType::Moment(m) => Some(m.date()),
+            TimeType::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
 
-        match *self {
-            TT::Moment(m) => Ok(m.month() == month.into()),
-            _ => Err(Error::CannotCompareMonthTo(self.name())),
+    /// The time of day of a `TimeType::Moment` or `TimeType::Time`
+    pub(crate) fn get_time_of_day(&self) -> Option<NaiveTime> {
+        match self {
+            TimeType::Moment(m) => Some(m.time()),
+            TimeType::Time(t) => Some(*t),
+            _ => None,
         }
     }
 
+    /// Check whether a `TimeType::Moment` or `TimeType::Date` is a certain weekday. Returns an
+    /// error if TimeType is neither.
+    pub fn is_a(&self, d: Day) -> Result<bool> {
+        self.get_calendar_date()
+            .map(|date| date.weekday() == d.into())
+            .ok_or_else(|| Error::NotAMoment(self.clone()))
+    }
+
+    /// Check whether a `TimeType::Moment` or `TimeType::Date` is in a certain month. Returns an
+    /// error if the TimeType is neither.
+    pub fn is_in(&self, month: Month) -> Result<bool> {
+        self.get_calendar_date()
+            .map(|date| date.month() == month.into())
+            .ok_or_else(|| Error::NotAMoment(self.clone()))
+    }
+
+    /// Check whether the TimeType is at a certain mark, see `Mark` for what matches
+    pub fn is(&self, mark: Mark) -> Result<bool> {
+        mark.matches(self)
+    }
+
+    /// Iterate from this moment on
+    ///
+    /// The step can either be an amount, like `TimeType::weeks(1)`, or a matcher, like
+    /// `Day::Monday`. With a matcher, the iteration goes day by day and only yields the days which
+    /// match.
+    pub fn every<S: IntoIncrement>(self, step: S) -> Occurrences {
+        Occurrences::new(self, step.into_increment())
+    }
+
+    /// Calculate the calendar-aware difference from this TimeType to `other`
+    ///
+    /// Both TimeTypes are calculated and must evaluate to a `TimeType::Moment`. The difference is
+    /// broken down into the passed `units`, respecting the real lengths of months and leap years.
+    /// See `difference::Difference::between()` for details.
+    pub fn difference(&self, other: &TimeType, units: &[Unit]) -> Result<Difference> {
+        let from = self.clone().calculate()?;
+        let from = from.get_moment().ok_or_else(|| Error::NotAMoment(from.clone()))?;
+
+        let to = other.clone().calculate()?;
+        let to = to.get_moment().ok_or_else(|| Error::NotAMoment(to.clone()))?;
+
+        Difference::between(from, to, units)
+    }
+
+    /// Calculate the first day of the fiscal year the current TimeType lies in
+    ///
+    /// Other than `end_of_year()`, this calculates the TimeType immediately.
+    ///
+    /// # Warning
+    ///
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the start
+    /// of the fiscal year will fail
+    pub fn start_of_fiscal_year(&self, cal: &FiscalCalendar) -> Result<TimeType> {
+        let date = self.calculate_date()?;
+        cal.start_of_year(date)
+            .map(|d| TimeType::moment(d.and_time(NaiveTime::MIN)))
+    }
+
+    /// Calculate the last day of the fiscal year the current TimeType lies in
+    ///
+    /// The end of a fiscal year is considered to be the last day of the fiscal year, not the last
+    /// second. Other than `end_of_year()`, this calculates the TimeType immediately.
+    ///
+    /// # Warning
+    ///
+    /// If the current TimeType does _not_ evaluate to a `TimeType::Moment`, calculating the end of
+    /// the fiscal year will fail
+    pub fn end_of_fiscal_year(&self, cal: &FiscalCalendar) -> Result<TimeType> {
+        let date = self.calculate_date()?;
+        cal.end_of_year(date)
+            .map(|d| TimeType::moment(d.and_time(NaiveTime::MIN)))
+    }
+
+    /// Get the fiscal year the current TimeType lies in, named after the year it nominally ends in
+    pub fn fiscal_year(&self, cal: &FiscalCalendar) -> Result<i32> {
+        cal.year(self.calculate_date()?)
+    }
+
+    /// Get the fiscal quarter (1 to 4) the current TimeType lies in
+    pub fn fiscal_quarter(&self, cal: &FiscalCalendar) -> Result<u32> {
+        cal.quarter(self.calculate_date()?)
+    }
+
+    /// Get the fiscal week the current TimeType lies in, starting at 1
+    pub fn fiscal_week(&self, cal: &FiscalCalendar) -> Result<u32> {
+        cal.week(self.calculate_date()?)
+    }
+
+    fn calculate_date(&self) -> Result<NaiveDate> {
+        let tt = self.clone().calculate()?;
+        tt.get_calendar_date().ok_or(Error::NotAMoment(tt))
+    }
+
     /// Get a string representation of the variant of the `TimeType` instance.
     pub fn name(&self) -> &'static str {
         use self::TimeType as TT;
@@ -414,6 +637,8 @@ impl TimeType {
             TT::Hours(..) => "Hours",
             TT::Minutes(..) => "Minutes",
             TT::Moment(..) => "Moment",
+            TT::Date(..) => "Date",
+            TT::Time(..) => "Time",
             TT::Months(..) => "Months",
             TT::Seconds(..) => "Seconds",
             TT::Subtraction(..) => "Subtraction",
@@ -422,7 +647,16 @@ impl TimeType {
     }
 
     pub fn calculate(self) -> Result<TimeType> {
-        do_calculate(self)
+        do_calculate(self, &mut Trace::disabled())
+    }
+
+    /// Calculate the TimeType and record every reduction step on the way
+    ///
+    /// The result is the same as the one of `calculate()`.
+    pub fn explain(self) -> Result<Explanation> {
+        let mut trace = Trace::enabled();
+        let result = do_calculate(self, &mut trace)?;
+        Ok(trace.into_explanation(result))
     }
 }
 
@@ -439,17 +673,17 @@ impl IntoTimeType for TimeType {
     }
 }
 
-fn do_calculate(tt: TimeType) -> Result<TimeType> {
+fn do_calculate(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
     match tt {
-        TT::Addition(a, b) => add(a, b),
-        TT::Subtraction(a, b) => sub(a, b),
-        TT::EndOfYear(inner) => end_of_year(*inner),
-        TT::EndOfMonth(inner) => end_of_month(*inner),
-        TT::EndOfDay(inner) => end_of_day(*inner),
-        TT::EndOfHour(inner) => end_of_hour(*inner),
-        TT::EndOfMinute(inner) => end_of_minute(*inner),
+        TT::Addition(a, b) => add(a, b, trace),
+        TT::Subtraction(a, b) => sub(a, b, trace),
+        TT::EndOfYear(inner) => end_of_year(*inner, trace),
+        TT::EndOfMonth(inner) => end_of_month(*inner, trace),
+        TT::EndOfDay(inner) => end_of_day(*inner, trace),
+        TT::EndOfHour(inner) => end_of_hour(*inner, trace),
+        TT::EndOfMinute(inner) => end_of_minute(*inner, trace),
         x => Ok(x),
     }
 }
@@ -458,10 +692,12 @@ fn do_calculate(tt: TimeType) -> Result<TimeType> {
 /// else b, cit returns an error
 ///
 /// Calling a end-of-year on a end-of-year yields end-of-year applied only once.
-fn end_of_year(tt: TimeType) -> Result<TimeType> {
+fn end_of_year(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match do_calculate(tt)? {
+    let input = trace.input(|| tt.clone().end_of_year());
+
+    let result = match do_calculate(tt, trace)? {
         els @ TT::Seconds(_)
         | els @ TT::Minutes(_)
         | els @ TT::Hours(_)
@@ -469,29 +705,36 @@ fn end_of_year(tt: TimeType) -> Result<TimeType> {
         | els @ TT::Months(_)
         | els @ TT::Years(_)
         | els @ TT::Addition(_, _)
-        | els @ TT::Subtraction(_, _) => Err(Error::CannotCalculateEndOfYearOn(els)),
+        | els @ TT::Subtraction(_, _)
+        | els @ TT::Time(_) => Err(Error::CannotCalculate(Operation::EndOfYear, els)),
         TT::Moment(m) => NaiveDate::from_ymd_opt(m.year(), 12, 31)
             .and_then(|nd| nd.and_hms_opt(0, 0, 0))
             .map(TT::moment)
-            .ok_or(Error::OutOfBounds(m.year(), 12, 31, 0, 0, 0))
-            .map_err(Error::from),
-
-        TT::EndOfYear(e) => do_calculate(*e),
-        TT::EndOfMonth(e) => do_calculate(*e),
-        TT::EndOfDay(e) => do_calculate(*e),
-        TT::EndOfHour(e) => do_calculate(*e),
-        TT::EndOfMinute(e) => do_calculate(*e),
-    }
+            .ok_or(Error::OutOfRange(m.year().into(), 12, 31, 0, 0, 0)),
+
+        TT::Date(d) => NaiveDate::from_ymd_opt(d.year(), 12, 31)
+            .map(TT::date)
+            .ok_or(Error::OutOfRange(d.year().into(), 12, 31, 0, 0, 0)),
+        TT::EndOfYear(e) => do_calculate(*e, trace),
+        TT::EndOfMonth(e) => do_calculate(*e, trace),
+        TT::EndOfDay(e) => do_calculate(*e, trace),
+        TT::EndOfHour(e) => do_calculate(*e, trace),
+        TT::EndOfMinute(e) => do_calculate(*e, trace),
+    };
+
+    trace.record(Rule::EndOfYear, input, result)
 }
 
 /// Evaluates the passed argument and if it is a `TT::Moment` it adjust its to the end of the month
 /// else returns an error
 ///
 /// Calling a end-of-month on a end-of-month yields end-of-month applied only once.
-fn end_of_month(tt: TimeType) -> Result<TimeType> {
+fn end_of_month(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match do_calculate(tt)? {
+    let input = trace.input(|| tt.clone().end_of_month());
+
+    let result = match do_calculate(tt, trace)? {
         els @ TT::Seconds(_)
         | els @ TT::Minutes(_)
         | els @ TT::Hours(_)
@@ -499,31 +742,41 @@ fn end_of_month(tt: TimeType) -> Result<TimeType> {
         | els @ TT::Months(_)
         | els @ TT::Years(_)
         | els @ TT::Addition(_, _)
-        | els @ TT::Subtraction(_, _) => Err(Error::CannotCalculateEndOfMonthOn(els)),
+        | els @ TT::Subtraction(_, _)
+        | els @ TT::Time(_) => Err(Error::CannotCalculate(Operation::EndOfMonth, els)),
         TT::Moment(m) => {
             let last_day = get_num_of_days_in_month(m.year() as i64, m.month() as i64) as u32;
             NaiveDate::from_ymd_opt(m.year(), m.month(), last_day)
                 .and_then(|nd| nd.and_hms_opt(0, 0, 0))
                 .map(TT::moment)
-                .ok_or(Error::OutOfBounds(m.year(), m.month(), last_day, 0, 0, 0))
-                .map_err(Error::from)
+                .ok_or(Error::OutOfRange(m.year().into(), m.month(), last_day, 0, 0, 0))
         },
-        TT::EndOfYear(e) => do_calculate(*e),
-        TT::EndOfMonth(e) => do_calculate(*e),
-        TT::EndOfDay(e) => do_calculate(*e),
-        TT::EndOfHour(e) => do_calculate(*e),
-        TT::EndOfMinute(e) => do_calculate(*e),
-    }
+        TT::Date(d) => {
+            let last_day = get_num_of_days_in_month(d.year() as i64, d.month() as i64) as u32;
+            NaiveDate::from_ymd_opt(d.year(), d.month(), last_day)
+                .map(TT::date)
+                .ok_or(Error::OutOfRange(d.year().into(), d.month(), last_day, 0, 0, 0))
+        },
+        TT::EndOfYear(e) => do_calculate(*e, trace),
+        TT::EndOfMonth(e) => do_calculate(*e, trace),
+        TT::EndOfDay(e) => do_calculate(*e, trace),
+        TT::EndOfHour(e) => do_calculate(*e, trace),
+        TT::EndOfMinute(e) => do_calculate(*e, trace),
+    };
+
+    trace.record(Rule::EndOfMonth, input, result)
 }
 
 /// Evaluates the passed argument and if it is a `TT::Moment` it adjust its to the end of the day
 /// else returns an error
 ///
 /// Calling a end-of-day on a end-of-day yields end-of-month applied only once.
-fn end_of_day(tt: TimeType) -> Result<TimeType> {
+fn end_of_day(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match do_calculate(tt)? {
+    let input = trace.input(|| tt.clone().end_of_day());
+
+    let result = match do_calculate(tt, trace)? {
         els @ TT::Seconds(_)
         | els @ TT::Minutes(_)
         | els @ TT::Hours(_)
@@ -531,28 +784,35 @@ fn end_of_day(tt: TimeType) -> Result<TimeType> {
         | els @ TT::Months(_)
         | els @ TT::Years(_)
         | els @ TT::Addition(_, _)
-        | els @ TT::Subtraction(_, _) => Err(Error::CannotCalculateEndOfMonthOn(els)),
+        | els @ TT::Subtraction(_, _)
+        | els @ TT::Date(_) => Err(Error::CannotCalculate(Operation::EndOfDay, els)),
         TT::Moment(m) => NaiveDate::from_ymd_opt(m.year(), m.month(), m.day())
             .and_then(|nd| nd.and_hms_opt(23, 59, 59))
             .map(TT::moment)
-            .ok_or(Error::OutOfBounds(m.year(), m.month(), m.day(), 23, 59, 59))
-            .map_err(Error::from),
-        TT::EndOfYear(e) => do_calculate(*e),
-        TT::EndOfMonth(e) => do_calculate(*e),
-        TT::EndOfDay(e) => do_calculate(*e),
-        TT::EndOfHour(e) => do_calculate(*e),
-        TT::EndOfMinute(e) => do_calculate(*e),
-    }
+            .ok_or(Error::OutOfRange(m.year().into(), m.month(), m.day(), 23, 59, 59)),
+        TT::Time(_) => NaiveTime::from_hms_opt(23, 59, 59)
+            .map(TT::time)
+            .ok_or(Error::OutOfRange(0, 1, 1, 23, 59, 59)),
+        TT::EndOfYear(e) => do_calculate(*e, trace),
+        TT::EndOfMonth(e) => do_calculate(*e, trace),
+        TT::EndOfDay(e) => do_calculate(*e, trace),
+        TT::EndOfHour(e) => do_calculate(*e, trace),
+        TT::EndOfMinute(e) => do_calculate(*e, trace),
+    };
+
+    trace.record(Rule::EndOfDay, input, result)
 }
 
 /// Evaluates the passed argument and if it is a `TT::Moment` it adjust its to the end of the hour
 /// else returns an error
 ///
 /// Calling a end-of-hour on a end-of-hour yields end-of-month applied only once.
-fn end_of_hour(tt: TimeType) -> Result<TimeType> {
+fn end_of_hour(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match do_calculate(tt)? {
+    let input = trace.input(|| tt.clone().end_of_hour());
+
+    let result = match do_calculate(tt, trace)? {
         els @ TT::Seconds(_)
         | els @ TT::Minutes(_)
         | els @ TT::Hours(_)
@@ -560,28 +820,35 @@ fn end_of_hour(tt: TimeType) -> Result<TimeType> {
         | els @ TT::Months(_)
         | els @ TT::Years(_)
         | els @ TT::Addition(_, _)
-        | els @ TT::Subtraction(_, _) => Err(Error::CannotCalculateEndOfMonthOn(els)),
+        | els @ TT::Subtraction(_, _)
+        | els @ TT::Date(_) => Err(Error::CannotCalculate(Operation::EndOfHour, els)),
         TT::Moment(m) => NaiveDate::from_ymd_opt(m.year(), m.month(), m.day())
             .and_then(|nd| nd.and_hms_opt(m.hour(), 59, 59))
             .map(TT::moment)
-            .ok_or(Error::OutOfBounds(m.year(), m.month(), m.day(), m.hour(), 59, 59))
-            .map_err(Error::from),
-        TT::EndOfYear(e) => do_calculate(*e),
-        TT::EndOfMonth(e) => do_calculate(*e),
-        TT::EndOfDay(e) => do_calculate(*e),
-        TT::EndOfHour(e) => do_calculate(*e),
-        TT::EndOfMinute(e) => do_calculate(*e),
-    }
+            .ok_or(Error::OutOfRange(m.year().into(), m.month(), m.day(), m.hour(), 59, 59)),
+        TT::Time(t) => NaiveTime::from_hms_opt(t.hour(), 59, 59)
+            .map(TT::time)
+            .ok_or(Error::OutOfRange(0, 1, 1, t.hour(), 59, 59)),
+        TT::EndOfYear(e) => do_calculate(*e, trace),
+        TT::EndOfMonth(e) => do_calculate(*e, trace),
+        TT::EndOfDay(e) => do_calculate(*e, trace),
+        TT::EndOfHour(e) => do_calculate(*e, trace),
+        TT::EndOfMinute(e) => do_calculate(*e, trace),
+    };
+
+    trace.record(Rule::EndOfHour, input, result)
 }
 
 /// Evaluates the passed argument and if it is a `TT::Moment` it adjust its to the end of the
 /// minute else returns an error
 ///
 /// Calling a end-of-minute on a end-of-minute yields end-of-month applied only once.
-fn end_of_minute(tt: TimeType) -> Result<TimeType> {
+fn end_of_minute(tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match do_calculate(tt)? {
+    let input = trace.input(|| tt.clone().end_of_minute());
+
+    let result = match do_calculate(tt, trace)? {
         els @ TT::Seconds(_)
         | els @ TT::Minutes(_)
         | els @ TT::Hours(_)
@@ -589,49 +856,62 @@ fn end_of_minute(tt: TimeType) -> Result<TimeType> {
         | els @ TT::Months(_)
         | els @ TT::Years(_)
         | els @ TT::Addition(_, _)
-        | els @ TT::Subtraction(_, _) => Err(Error::CannotCalculateEndOfMonthOn(els)),
+        | els @ TT::Subtraction(_, _)
+        | els @ TT::Date(_) => Err(Error::CannotCalculate(Operation::EndOfMinute, els)),
         TT::Moment(m) => NaiveDate::from_ymd_opt(m.year(), m.month(), m.day())
             .and_then(|nd| nd.and_hms_opt(m.hour(), m.minute(), 59))
             .map(TT::moment)
-            .ok_or(Error::OutOfBounds(
-                m.year(),
+            .ok_or(Error::OutOfRange(
+                m.year().into(),
                 m.month(),
                 m.day(),
                 m.hour(),
                 m.minute(),
                 59,
-            ))
-            .map_err(Error::from),
-        TT::EndOfYear(e) => do_calculate(*e),
-        TT::EndOfMonth(e) => do_calculate(*e),
-        TT::EndOfDay(e) => do_calculate(*e),
-        TT::EndOfHour(e) => do_calculate(*e),
-        TT::EndOfMinute(e) => do_calculate(*e),
-    }
+            )),
+        TT::Time(t) => NaiveTime::from_hms_opt(t.hour(), t.minute(), 59)
+            .map(TT::time)
+            .ok_or(Error::OutOfRange(0, 1, 1, t.hour(), t.minute(), 59)),
+        TT::EndOfYear(e) => do_calculate(*e, trace),
+        TT::EndOfMonth(e) => do_calculate(*e, trace),
+        TT::EndOfDay(e) => do_calculate(*e, trace),
+        TT::EndOfHour(e) => do_calculate(*e, trace),
+        TT::EndOfMinute(e) => do_calculate(*e, trace),
+    };
+
+    trace.record(Rule::EndOfMinute, input, result)
 }
 
 #[allow(clippy::boxed_local)]
-fn add(a: Box<TimeType>, b: Box<TimeType>) -> Result<TimeType> {
+fn add(a: Box<TimeType>, b: Box<TimeType>, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
     match (*a, *b) {
-        (TT::Moment(mom), thing) => add_to_moment(mom, thing),
+        (TT::Moment(mom), thing) => add_to_moment(mom, thing, trace),
         (thing, TT::Moment(mom)) => Err(Error::CannotAdd(thing, TT::Moment(mom))),
+        (TT::Date(d), thing) => add_to_date(d, thing, trace),
+        (TT::Time(t), thing) => add_to_time(t, thing, trace),
+
+        (TT::Seconds(a), other) => add_to_seconds(a, other, trace),
+        (TT::Minutes(a), other) => add_to_minutes(a, other, trace),
+        (TT::Hours(a), other) => add_to_hours(a, other, trace),
+        (TT::Days(a), other) => add_to_days(a, other, trace),
+        (TT::Months(a), other) => add_to_months(a, other, trace),
+        (TT::Years(a), other) => add_to_years(a, other, trace),
 
-        (TT::Seconds(a), other) => add_to_seconds(a, other),
-        (TT::Minutes(a), other) => add_to_minutes(a, other),
-        (TT::Hours(a), other) => add_to_hours(a, other),
-        (TT::Days(a), other) => add_to_days(a, other),
-        (TT::Months(a), other) => add_to_months(a, other),
-        (TT::Years(a), other) => add_to_years(a, other),
-
-        (TT::Addition(a, b), other) => add(a, b).map(Box::new).and_then(|bx| add(bx, Box::new(other))),
-        (other, TT::Addition(a, b)) => add(a, b).map(Box::new).and_then(|bx| add(Box::new(other), bx)),
-        (TT::Subtraction(a, b), other) => sub(a, b).map(Box::new).and_then(|bx| add(Box::new(other), bx)),
-        (other, TT::Subtraction(a, b)) => do_calculate(*a)
+        (TT::Addition(a, b), other) => add(a, b, trace)
+            .map(Box::new)
+            .and_then(|bx| add(bx, Box::new(other), trace)),
+        (other, TT::Addition(a, b)) => add(a, b, trace)
+            .map(Box::new)
+            .and_then(|bx| add(Box::new(other), bx, trace)),
+        (TT::Subtraction(a, b), other) => sub(a, b, trace)
+            .map(Box::new)
+            .and_then(|bx| add(Box::new(other), bx, trace)),
+        (other, TT::Subtraction(a, b)) => do_calculate(*a, trace)
             .map(Box::new)
-            .and_then(|bx| add(Box::new(other), bx))
-            .and_then(|rx| sub(Box::new(rx), b)),
+            .and_then(|bx| add(Box::new(other), bx, trace))
+            .and_then(|rx| sub(Box::new(rx), b, trace)),
 
         (TT::EndOfYear(e), other) => Err(Error::CannotAdd(other, TT::EndOfYear(e))),
         (other, TT::EndOfYear(e)) => Err(Error::CannotAdd(other, TT::EndOfYear(e))),
@@ -651,152 +931,257 @@ fn add(a: Box<TimeType>, b: Box<TimeType>) -> Result<TimeType> {
     }
 }
 
-fn add_to_seconds(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_seconds(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount)),
-        TT::Minutes(a) => Ok(TT::Seconds(a * 60 + amount)),
-        TT::Hours(a) => Ok(TT::Seconds(a * 60 * 60 + amount)),
-        TT::Days(a) => Ok(TT::Seconds(a * 60 * 60 * 24 + amount)),
-        TT::Months(a) => Ok(TT::Seconds(a * 60 * 60 * 24 * 30 + amount)),
-        TT::Years(a) => Ok(TT::Seconds(a * 60 * 60 * 24 * 30 * 12 + amount)),
+    let input = trace.input(|| TT::Seconds(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Seconds(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(a) + i128::from(amount), TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(a) * 60 + i128::from(amount), TT::Minutes(a)).map(TT::Seconds),
+        TT::Hours(a) => checked(i128::from(a) * 60 * 60 + i128::from(amount), TT::Hours(a)).map(TT::Seconds),
+        TT::Days(a) => checked(i128::from(a) * 60 * 60 * 24 + i128::from(amount), TT::Days(a)).map(TT::Seconds),
+        TT::Months(a) => {
+            checked(i128::from(a) * 60 * 60 * 24 * 30 + i128::from(amount), TT::Months(a)).map(TT::Seconds)
+        },
+        TT::Years(a) => checked(
+            i128::from(a) * 60 * 60 * 24 * 30 * 12 + i128::from(amount),
+            TT::Years(a),
+        )
+        .map(TT::Seconds),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Seconds(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Seconds(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Seconds(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Seconds(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Seconds(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Seconds(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Seconds(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Seconds(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_seconds(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_seconds(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => add_to_seconds(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_seconds(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToSeconds, input, result)
 }
 
-fn add_to_minutes(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_minutes(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount * 60)),
-        TT::Minutes(a) => Ok(TT::Minutes(a + amount)),
-        TT::Hours(a) => Ok(TT::Minutes(a * 60 + amount)),
-        TT::Days(a) => Ok(TT::Minutes(a * 60 * 24 + amount)),
-        TT::Months(a) => Ok(TT::Minutes(a * 60 * 24 * 30 + amount)),
-        TT::Years(a) => Ok(TT::Minutes(a * 60 * 24 * 30 * 12 + amount)),
+    let input = trace.input(|| TT::Minutes(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Minutes(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(a) + i128::from(amount) * 60, TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(a) + i128::from(amount), TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(a) * 60 + i128::from(amount), TT::Hours(a)).map(TT::Minutes),
+        TT::Days(a) => checked(i128::from(a) * 60 * 24 + i128::from(amount), TT::Days(a)).map(TT::Minutes),
+        TT::Months(a) => checked(i128::from(a) * 60 * 24 * 30 + i128::from(amount), TT::Months(a)).map(TT::Minutes),
+        TT::Years(a) => checked(i128::from(a) * 60 * 24 * 30 * 12 + i128::from(amount), TT::Years(a)).map(TT::Minutes),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Minutes(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Minutes(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Minutes(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Minutes(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Minutes(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Minutes(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Minutes(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Minutes(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_minutes(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_minutes(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => add_to_minutes(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_minutes(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToMinutes, input, result)
 }
 
-fn add_to_hours(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_hours(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount * 60 * 60)),
-        TT::Minutes(a) => Ok(TT::Minutes(a + amount * 60)),
-        TT::Hours(a) => Ok(TT::Hours(a + amount)),
-        TT::Days(a) => Ok(TT::Hours(a * 24 + amount)),
-        TT::Months(a) => Ok(TT::Hours(a * 24 * 30 + amount)),
-        TT::Years(a) => Ok(TT::Hours(a * 24 * 30 * 12 + amount)),
+    let input = trace.input(|| TT::Hours(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Hours(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(a) + i128::from(amount) * 60 * 60, TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(a) + i128::from(amount) * 60, TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(a) + i128::from(amount), TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(a) * 24 + i128::from(amount), TT::Days(a)).map(TT::Hours),
+        TT::Months(a) => checked(i128::from(a) * 24 * 30 + i128::from(amount), TT::Months(a)).map(TT::Hours),
+        TT::Years(a) => checked(i128::from(a) * 24 * 30 * 12 + i128::from(amount), TT::Years(a)).map(TT::Hours),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Hours(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Hours(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Hours(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Hours(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Hours(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Hours(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Hours(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Hours(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_hours(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_hours(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => add_to_hours(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_hours(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToHours, input, result)
 }
 
-fn add_to_days(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_days(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount * 24 * 60 * 60)),
-        TT::Minutes(a) => Ok(TT::Minutes(a + amount * 24 * 60)),
-        TT::Hours(a) => Ok(TT::Hours(a + amount * 24)),
-        TT::Days(a) => Ok(TT::Days(a + amount)),
-        TT::Months(a) => Ok(TT::Days(a * 30 + amount)),
-        TT::Years(a) => Ok(TT::Days(a * 30 * 12 + amount)),
+    let input = trace.input(|| TT::Days(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Days(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(a) + i128::from(amount) * 24 * 60 * 60, TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(a) + i128::from(amount) * 24 * 60, TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(a) + i128::from(amount) * 24, TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(a) + i128::from(amount), TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(a) * 30 + i128::from(amount), TT::Months(a)).map(TT::Days),
+        TT::Years(a) => checked(i128::from(a) * 30 * 12 + i128::from(amount), TT::Years(a)).map(TT::Days),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Days(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Days(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Days(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Days(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Days(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Days(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Days(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Days(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_days(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_days(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => add_to_days(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_days(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToDays, input, result)
 }
 
-fn add_to_months(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_months(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount * 30 * 24 * 60 * 60)),
-        TT::Minutes(a) => Ok(TT::Minutes(a + amount * 30 * 24 * 60)),
-        TT::Hours(a) => Ok(TT::Hours(a + amount * 30 * 24)),
-        TT::Days(a) => Ok(TT::Days(a + amount * 30)),
-        TT::Months(a) => Ok(TT::Months(a + amount)),
-        TT::Years(a) => Ok(TT::Months(a * 12 + amount)),
+    let input = trace.input(|| TT::Months(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Months(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => {
+            checked(i128::from(a) + i128::from(amount) * 30 * 24 * 60 * 60, TT::Seconds(a)).map(TT::Seconds)
+        },
+        TT::Minutes(a) => checked(i128::from(a) + i128::from(amount) * 30 * 24 * 60, TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(a) + i128::from(amount) * 30 * 24, TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(a) + i128::from(amount) * 30, TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(a) + i128::from(amount), TT::Months(a)).map(TT::Months),
+        TT::Years(a) => checked(i128::from(a) * 12 + i128::from(amount), TT::Years(a)).map(TT::Months),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Months(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Months(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Months(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Months(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Months(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Months(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Months(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Months(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_months(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_months(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => add_to_months(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_months(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToMonths, input, result)
 }
 
-fn add_to_years(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn add_to_years(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(a + amount * 12 * 30 * 24 * 60 * 60)),
-        TT::Minutes(a) => Ok(TT::Minutes(a + amount * 12 * 30 * 24 * 60)),
-        TT::Hours(a) => Ok(TT::Hours(a + amount * 12 * 30 * 24)),
-        TT::Days(a) => Ok(TT::Days(a + amount * 12 * 30)),
-        TT::Months(a) => Ok(TT::Months(a + amount * 12)),
-        TT::Years(a) => Ok(TT::Years(a + amount)),
+    let input = trace.input(|| TT::Years(amount) + tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Add, TT::Years(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(
+            i128::from(a) + i128::from(amount) * 12 * 30 * 24 * 60 * 60,
+            TT::Seconds(a),
+        )
+        .map(TT::Seconds),
+        TT::Minutes(a) => {
+            checked(i128::from(a) + i128::from(amount) * 12 * 30 * 24 * 60, TT::Minutes(a)).map(TT::Minutes)
+        },
+        TT::Hours(a) => checked(i128::from(a) + i128::from(amount) * 12 * 30 * 24, TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(a) + i128::from(amount) * 12 * 30, TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(a) + i128::from(amount) * 12, TT::Months(a)).map(TT::Months),
+        TT::Years(a) => checked(i128::from(a) + i128::from(amount), TT::Years(a)).map(TT::Years),
         TT::Moment(m) => Err(Error::CannotAdd(TT::Years(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Years(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Years(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Years(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Years(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Years(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Years(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Years(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => add_to_years(amount, add(b, c)?),
-        TT::Subtraction(b, c) => add_to_years(amount, sub(b, c)?),
+        TT::Addition(b, c) => add_to_years(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_years(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToYears, input, result)
+}
+
+// Whether the amount is bigger than the whole range of chrono, so adding it to or subtracting it
+// from any moment leaves that range
+fn exceeds_moment_range(tt: &TimeType) -> bool {
+    use crate::timetype::TimeType as TT;
+
+    let seconds = NaiveDateTime::MAX
+        .signed_duration_since(NaiveDateTime::MIN)
+        .num_seconds()
+        .unsigned_abs();
+    let years = (i64::from(NaiveDate::MAX.year()) - i64::from(NaiveDate::MIN.year()) + 1).unsigned_abs();
+
+    match *tt {
+        TT::Seconds(a) => a.unsigned_abs() > seconds,
+        TT::Minutes(a) => a.unsigned_abs() > seconds / 60,
+        TT::Hours(a) => a.unsigned_abs() > seconds / 3600,
+        TT::Days(a) => a.unsigned_abs() > seconds / 86400,
+        TT::Months(a) => a.unsigned_abs() > years * 12,
+        TT::Years(a) => a.unsigned_abs() > years,
+        _ => false,
     }
 }
 
-fn add_to_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
+fn add_to_moment(mom: NaiveDateTime, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
+    let input = trace.input(|| TT::moment(mom) + tt.clone());
+
+    let overflow = |rhs: TimeType| Error::Overflow(Operation::Add, TT::Moment(mom), rhs);
+
+    if exceeds_moment_range(&tt) {
+        return Err(overflow(tt));
+    }
+
+    let result = match tt {
         TT::Seconds(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
-            let s = mom.second() as i64 + a;
+            let s = (mom.second() as i64)
+                .checked_add(a)
+                .ok_or_else(|| overflow(TT::Seconds(a)))?;
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Minutes(a) => {
@@ -804,58 +1189,61 @@ fn add_to_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
-            let mi = mom.minute() as i64 + a;
+            let mi = (mom.minute() as i64)
+                .checked_add(a)
+                .ok_or_else(|| overflow(TT::Minutes(a)))?;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Hours(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
-            let h = mom.hour() as i64 + a;
+            let h = (mom.hour() as i64)
+                .checked_add(a)
+                .ok_or_else(|| overflow(TT::Hours(a)))?;
             let mi = mom.minute() as i64;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Days(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
-            let d = mom.day() as i64 + a;
+            let d = (mom.day() as i64).checked_add(a).ok_or_else(|| overflow(TT::Days(a)))?;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Months(a) => {
             let y = mom.year() as i64;
-            let mo = mom.month() as i64 + a;
+            let mo = (mom.month() as i64)
+                .checked_add(a)
+                .ok_or_else(|| overflow(TT::Months(a)))?;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
@@ -863,16 +1251,17 @@ fn add_to_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Years(a) => {
-            let y = mom.year() as i64 + a;
+            let y = (mom.year() as i64)
+                .checked_add(a)
+                .ok_or_else(|| overflow(TT::Years(a)))?;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
@@ -881,45 +1270,119 @@ fn add_to_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
 
             let (y, mo, d, h, mi, s) = adjust_times_add(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Moment(m) => Err(Error::CannotAdd(TT::Moment(mom), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Moment(mom), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Moment(mom), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Moment(mom), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Moment(mom), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Moment(mom), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Moment(mom), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Moment(mom), TT::EndOfMinute(e))),
-        TT::Addition(a, b) => add_to_moment(mom, add(a, b)?),
-        TT::Subtraction(a, b) => add_to_moment(mom, sub(a, b)?),
-    }
+        TT::Addition(a, b) => add_to_moment(mom, add(a, b, trace)?, trace),
+        TT::Subtraction(a, b) => add_to_moment(mom, sub(a, b, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToMoment, input, result)
+}
+
+fn add_to_date(date: NaiveDate, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
+    use crate::timetype::TimeType as TT;
+
+    let input = trace.input(|| TT::date(date) + tt.clone());
+
+    let result = match tt {
+        TT::Days(a) => chrono::Duration::try_days(a)
+            .and_then(|delta| date.checked_add_signed(delta))
+            .map(TT::date)
+            .ok_or(Error::Overflow(Operation::Add, TT::Date(date), TT::Days(a))),
+        amount @ TT::Months(_) | amount @ TT::Years(_) => {
+            // same rules as for moments, the time of day just does not matter
+            let midnight = date.and_time(NaiveTime::MIN);
+            match add_to_moment(midnight, amount, &mut Trace::disabled())? {
+                TT::Moment(m) => Ok(TT::date(m.date())),
+                other => Err(Error::NotAMoment(other)),
+            }
+        },
+        TT::Time(t) => Ok(TT::moment(date.and_time(t))),
+        TT::Seconds(a) => Err(Error::CannotAdd(TT::Date(date), TT::Seconds(a))),
+        TT::Minutes(a) => Err(Error::CannotAdd(TT::Date(date), TT::Minutes(a))),
+        TT::Hours(a) => Err(Error::CannotAdd(TT::Date(date), TT::Hours(a))),
+        TT::Moment(m) => Err(Error::CannotAdd(TT::Date(date), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotAdd(TT::Date(date), TT::Date(d))),
+        TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Date(date), TT::EndOfYear(e))),
+        TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Date(date), TT::EndOfMonth(e))),
+        TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Date(date), TT::EndOfDay(e))),
+        TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Date(date), TT::EndOfHour(e))),
+        TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Date(date), TT::EndOfMinute(e))),
+        TT::Addition(b, c) => add_to_date(date, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_date(date, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToDate, input, result)
+}
+
+fn add_to_time(time: NaiveTime, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
+    use crate::timetype::TimeType as TT;
+
+    let input = trace.input(|| TT::time(time) + tt.clone());
+
+    let result = match tt {
+        TT::Seconds(a) => Ok(TT::time(wrap_time(time, i128::from(a)))),
+        TT::Minutes(a) => Ok(TT::time(wrap_time(time, i128::from(a) * 60))),
+        TT::Hours(a) => Ok(TT::time(wrap_time(time, i128::from(a) * 60 * 60))),
+        TT::Date(d) => Ok(TT::moment(d.and_time(time))),
+        TT::Days(a) => Err(Error::CannotAdd(TT::Time(time), TT::Days(a))),
+        TT::Months(a) => Err(Error::CannotAdd(TT::Time(time), TT::Months(a))),
+        TT::Years(a) => Err(Error::CannotAdd(TT::Time(time), TT::Years(a))),
+        TT::Moment(m) => Err(Error::CannotAdd(TT::Time(time), TT::Moment(m))),
+        TT::Time(t) => Err(Error::CannotAdd(TT::Time(time), TT::Time(t))),
+        TT::EndOfYear(e) => Err(Error::CannotAdd(TT::Time(time), TT::EndOfYear(e))),
+        TT::EndOfMonth(e) => Err(Error::CannotAdd(TT::Time(time), TT::EndOfMonth(e))),
+        TT::EndOfDay(e) => Err(Error::CannotAdd(TT::Time(time), TT::EndOfDay(e))),
+        TT::EndOfHour(e) => Err(Error::CannotAdd(TT::Time(time), TT::EndOfHour(e))),
+        TT::EndOfMinute(e) => Err(Error::CannotAdd(TT::Time(time), TT::EndOfMinute(e))),
+        TT::Addition(b, c) => add_to_time(time, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => add_to_time(time, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::AddToTime, input, result)
 }
 
 #[allow(clippy::boxed_local)]
-fn sub(a: Box<TimeType>, b: Box<TimeType>) -> Result<TimeType> {
+fn sub(a: Box<TimeType>, b: Box<TimeType>, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
     match (*a, *b) {
-        (TT::Moment(mom), thing) => sub_from_moment(mom, thing),
-        (TT::Seconds(a), other) => sub_from_seconds(a, other),
-        (TT::Minutes(a), other) => sub_from_minutes(a, other),
-        (TT::Hours(a), other) => sub_from_hours(a, other),
-        (TT::Days(a), other) => sub_from_days(a, other),
-        (TT::Months(a), other) => sub_from_months(a, other),
-        (TT::Years(a), other) => sub_from_years(a, other),
-
-        (TT::Subtraction(a, b), other) => sub(a, b).map(Box::new).and_then(|bx| sub(bx, Box::new(other))),
-        (other, TT::Subtraction(a, b)) => sub(a, b).map(Box::new).and_then(|bx| sub(Box::new(other), bx)),
-        (TT::Addition(a, b), other) => add(a, b).map(Box::new).and_then(|bx| sub(bx, Box::new(other))),
-        (other, TT::Addition(a, b)) => do_calculate(*a)
+        (TT::Moment(mom), thing) => sub_from_moment(mom, thing, trace),
+        (TT::Date(d), thing) => sub_from_date(d, thing, trace),
+        (TT::Time(t), thing) => sub_from_time(t, thing, trace),
+        (TT::Seconds(a), other) => sub_from_seconds(a, other, trace),
+        (TT::Minutes(a), other) => sub_from_minutes(a, other, trace),
+        (TT::Hours(a), other) => sub_from_hours(a, other, trace),
+        (TT::Days(a), other) => sub_from_days(a, other, trace),
+        (TT::Months(a), other) => sub_from_months(a, other, trace),
+        (TT::Years(a), other) => sub_from_years(a, other, trace),
+
+        (TT::Subtraction(a, b), other) => sub(a, b, trace)
             .map(Box::new)
-            .and_then(|bx| sub(Box::new(other), bx))
-            .and_then(|rx| add(Box::new(rx), b)),
+            .and_then(|bx| sub(bx, Box::new(other), trace)),
+        (other, TT::Subtraction(a, b)) => sub(a, b, trace)
+            .map(Box::new)
+            .and_then(|bx| sub(Box::new(other), bx, trace)),
+        (TT::Addition(a, b), other) => add(a, b, trace)
+            .map(Box::new)
+            .and_then(|bx| sub(bx, Box::new(other), trace)),
+        (other, TT::Addition(a, b)) => do_calculate(*a, trace)
+            .map(Box::new)
+            .and_then(|bx| sub(Box::new(other), bx, trace))
+            .and_then(|rx| add(Box::new(rx), b, trace)),
 
         (TT::EndOfYear(e), other) => Err(Error::CannotSub(other, TT::EndOfYear(e))),
         (other, TT::EndOfYear(e)) => Err(Error::CannotSub(other, TT::EndOfYear(e))),
@@ -939,152 +1402,235 @@ fn sub(a: Box<TimeType>, b: Box<TimeType>) -> Result<TimeType> {
     }
 }
 
-fn sub_from_seconds(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_seconds(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount - a)),
-        TT::Minutes(a) => Ok(TT::Seconds(amount - a * 60)),
-        TT::Hours(a) => Ok(TT::Seconds(amount - a * 60 * 60)),
-        TT::Days(a) => Ok(TT::Seconds(amount - a * 60 * 60 * 24)),
-        TT::Months(a) => Ok(TT::Seconds(amount - a * 60 * 60 * 24 * 30)),
-        TT::Years(a) => Ok(TT::Seconds(amount - a * 60 * 60 * 24 * 30 * 12)),
+    let input = trace.input(|| TT::Seconds(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Seconds(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(amount) - i128::from(a), TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(amount) - i128::from(a) * 60, TT::Minutes(a)).map(TT::Seconds),
+        TT::Hours(a) => checked(i128::from(amount) - i128::from(a) * 60 * 60, TT::Hours(a)).map(TT::Seconds),
+        TT::Days(a) => checked(i128::from(amount) - i128::from(a) * 60 * 60 * 24, TT::Days(a)).map(TT::Seconds),
+        TT::Months(a) => {
+            checked(i128::from(amount) - i128::from(a) * 60 * 60 * 24 * 30, TT::Months(a)).map(TT::Seconds)
+        },
+        TT::Years(a) => checked(
+            i128::from(amount) - i128::from(a) * 60 * 60 * 24 * 30 * 12,
+            TT::Years(a),
+        )
+        .map(TT::Seconds),
         TT::Moment(m) => Err(Error::CannotSub(TT::Seconds(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Seconds(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Seconds(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Seconds(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Seconds(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Seconds(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Seconds(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Seconds(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_seconds(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_seconds(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_seconds(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_seconds(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromSeconds, input, result)
 }
 
-fn sub_from_minutes(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_minutes(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount * 60 - a)),
-        TT::Minutes(a) => Ok(TT::Minutes(amount - a)),
-        TT::Hours(a) => Ok(TT::Minutes(amount - a * 60)),
-        TT::Days(a) => Ok(TT::Minutes(amount - a * 60 * 24)),
-        TT::Months(a) => Ok(TT::Minutes(amount - a * 60 * 24 * 30)),
-        TT::Years(a) => Ok(TT::Minutes(amount - a * 60 * 24 * 30 * 12)),
+    let input = trace.input(|| TT::Minutes(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Minutes(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(amount) * 60 - i128::from(a), TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(amount) - i128::from(a), TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(amount) - i128::from(a) * 60, TT::Hours(a)).map(TT::Minutes),
+        TT::Days(a) => checked(i128::from(amount) - i128::from(a) * 60 * 24, TT::Days(a)).map(TT::Minutes),
+        TT::Months(a) => checked(i128::from(amount) - i128::from(a) * 60 * 24 * 30, TT::Months(a)).map(TT::Minutes),
+        TT::Years(a) => checked(i128::from(amount) - i128::from(a) * 60 * 24 * 30 * 12, TT::Years(a)).map(TT::Minutes),
         TT::Moment(m) => Err(Error::CannotSub(TT::Minutes(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Minutes(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Minutes(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Minutes(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Minutes(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Minutes(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Minutes(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Minutes(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_minutes(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_minutes(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_minutes(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_minutes(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromMinutes, input, result)
 }
 
-fn sub_from_hours(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_hours(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount * 60 * 60 - a)),
-        TT::Minutes(a) => Ok(TT::Minutes(amount * 60 - a)),
-        TT::Hours(a) => Ok(TT::Hours(amount - a)),
-        TT::Days(a) => Ok(TT::Hours(amount - a * 24)),
-        TT::Months(a) => Ok(TT::Hours(amount - a * 24 * 30)),
-        TT::Years(a) => Ok(TT::Hours(amount - a * 24 * 30 * 12)),
+    let input = trace.input(|| TT::Hours(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Hours(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(amount) * 60 * 60 - i128::from(a), TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(amount) * 60 - i128::from(a), TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(amount) - i128::from(a), TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(amount) - i128::from(a) * 24, TT::Days(a)).map(TT::Hours),
+        TT::Months(a) => checked(i128::from(amount) - i128::from(a) * 24 * 30, TT::Months(a)).map(TT::Hours),
+        TT::Years(a) => checked(i128::from(amount) - i128::from(a) * 24 * 30 * 12, TT::Years(a)).map(TT::Hours),
         TT::Moment(m) => Err(Error::CannotSub(TT::Hours(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Hours(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Hours(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Hours(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Hours(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Hours(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Hours(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Hours(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_hours(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_hours(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_hours(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_hours(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromHours, input, result)
 }
 
-fn sub_from_days(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_days(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount * 24 * 60 * 60 - a)),
-        TT::Minutes(a) => Ok(TT::Minutes(amount * 24 * 60 - a)),
-        TT::Hours(a) => Ok(TT::Hours(amount * 24 - a)),
-        TT::Days(a) => Ok(TT::Days(amount - a)),
-        TT::Months(a) => Ok(TT::Days(amount - a * 30)),
-        TT::Years(a) => Ok(TT::Days(amount - a * 30 * 12)),
+    let input = trace.input(|| TT::Days(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Days(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(i128::from(amount) * 24 * 60 * 60 - i128::from(a), TT::Seconds(a)).map(TT::Seconds),
+        TT::Minutes(a) => checked(i128::from(amount) * 24 * 60 - i128::from(a), TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(amount) * 24 - i128::from(a), TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(amount) - i128::from(a), TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(amount) - i128::from(a) * 30, TT::Months(a)).map(TT::Days),
+        TT::Years(a) => checked(i128::from(amount) - i128::from(a) * 30 * 12, TT::Years(a)).map(TT::Days),
         TT::Moment(m) => Err(Error::CannotSub(TT::Days(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Days(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Days(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Days(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Days(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Days(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Days(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Days(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_days(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_days(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_days(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_days(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromDays, input, result)
 }
 
-fn sub_from_months(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_months(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount * 30 * 24 * 60 * 60 - a)),
-        TT::Minutes(a) => Ok(TT::Minutes(amount * 30 * 24 * 60 - a)),
-        TT::Hours(a) => Ok(TT::Hours(amount * 30 * 24 - a)),
-        TT::Days(a) => Ok(TT::Days(amount * 30 - a)),
-        TT::Months(a) => Ok(TT::Months(amount - a)),
-        TT::Years(a) => Ok(TT::Months(amount - a * 12)),
+    let input = trace.input(|| TT::Months(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Months(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => {
+            checked(i128::from(amount) * 30 * 24 * 60 * 60 - i128::from(a), TT::Seconds(a)).map(TT::Seconds)
+        },
+        TT::Minutes(a) => checked(i128::from(amount) * 30 * 24 * 60 - i128::from(a), TT::Minutes(a)).map(TT::Minutes),
+        TT::Hours(a) => checked(i128::from(amount) * 30 * 24 - i128::from(a), TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(amount) * 30 - i128::from(a), TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(amount) - i128::from(a), TT::Months(a)).map(TT::Months),
+        TT::Years(a) => checked(i128::from(amount) - i128::from(a) * 12, TT::Years(a)).map(TT::Months),
         TT::Moment(m) => Err(Error::CannotSub(TT::Months(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Months(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Months(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Months(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Months(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Months(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Months(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Months(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_months(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_months(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_months(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_months(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromMonths, input, result)
 }
 
-fn sub_from_years(amount: i64, tt: TimeType) -> Result<TimeType> {
+fn sub_from_years(amount: i64, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
-        TT::Seconds(a) => Ok(TT::Seconds(amount * 12 * 30 * 24 * 60 * 60 - a)),
-        TT::Minutes(a) => Ok(TT::Minutes(amount * 12 * 30 * 24 * 60 - a)),
-        TT::Hours(a) => Ok(TT::Hours(amount * 12 * 30 * 24 - a)),
-        TT::Days(a) => Ok(TT::Days(amount * 12 * 30 - a)),
-        TT::Months(a) => Ok(TT::Months(amount * 12 - a)),
-        TT::Years(a) => Ok(TT::Years(amount - a)),
+    let input = trace.input(|| TT::Years(amount) - tt.clone());
+
+    let checked = |value: i128, rhs: TimeType| {
+        i64::try_from(value).map_err(|_| Error::Overflow(Operation::Subtract, TT::Years(amount), rhs))
+    };
+
+    let result = match tt {
+        TT::Seconds(a) => checked(
+            i128::from(amount) * 12 * 30 * 24 * 60 * 60 - i128::from(a),
+            TT::Seconds(a),
+        )
+        .map(TT::Seconds),
+        TT::Minutes(a) => {
+            checked(i128::from(amount) * 12 * 30 * 24 * 60 - i128::from(a), TT::Minutes(a)).map(TT::Minutes)
+        },
+        TT::Hours(a) => checked(i128::from(amount) * 12 * 30 * 24 - i128::from(a), TT::Hours(a)).map(TT::Hours),
+        TT::Days(a) => checked(i128::from(amount) * 12 * 30 - i128::from(a), TT::Days(a)).map(TT::Days),
+        TT::Months(a) => checked(i128::from(amount) * 12 - i128::from(a), TT::Months(a)).map(TT::Months),
+        TT::Years(a) => checked(i128::from(amount) - i128::from(a), TT::Years(a)).map(TT::Years),
         TT::Moment(m) => Err(Error::CannotSub(TT::Years(amount), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Years(amount), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Years(amount), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Years(amount), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Years(amount), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Years(amount), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Years(amount), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Years(amount), TT::EndOfMinute(e))),
-        TT::Addition(b, c) => sub_from_years(amount, add(b, c)?),
-        TT::Subtraction(b, c) => sub_from_years(amount, sub(b, c)?),
-    }
+        TT::Addition(b, c) => sub_from_years(amount, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_years(amount, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromYears, input, result)
 }
 
-fn sub_from_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
+fn sub_from_moment(mom: NaiveDateTime, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
     use crate::timetype::TimeType as TT;
 
-    match tt {
+    let input = trace.input(|| TT::moment(mom) - tt.clone());
+
+    let overflow = |rhs: TimeType| Error::Overflow(Operation::Subtract, TT::Moment(mom), rhs);
+
+    if exceeds_moment_range(&tt) {
+        return Err(overflow(tt));
+    }
+
+    let result = match tt {
         TT::Seconds(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
-            let s = mom.second() as i64 - a;
+            let s = (mom.second() as i64)
+                .checked_sub(a)
+                .ok_or_else(|| overflow(TT::Seconds(a)))?;
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Minutes(a) => {
@@ -1092,58 +1638,61 @@ fn sub_from_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
-            let mi = mom.minute() as i64 - a;
+            let mi = (mom.minute() as i64)
+                .checked_sub(a)
+                .ok_or_else(|| overflow(TT::Minutes(a)))?;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Hours(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
-            let h = mom.hour() as i64 - a;
+            let h = (mom.hour() as i64)
+                .checked_sub(a)
+                .ok_or_else(|| overflow(TT::Hours(a)))?;
             let mi = mom.minute() as i64;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Days(a) => {
             let y = mom.year() as i64;
             let mo = mom.month() as i64;
-            let d = mom.day() as i64 - a;
+            let d = (mom.day() as i64).checked_sub(a).ok_or_else(|| overflow(TT::Days(a)))?;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
             let s = mom.second() as i64;
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Months(a) => {
             let y = mom.year() as i64;
-            let mo = mom.month() as i64 - a;
+            let mo = (mom.month() as i64)
+                .checked_sub(a)
+                .ok_or_else(|| overflow(TT::Months(a)))?;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
             let mi = mom.minute() as i64;
@@ -1151,16 +1700,17 @@ fn sub_from_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Years(a) => {
-            let y = mom.year() as i64 - a;
+            let y = (mom.year() as i64)
+                .checked_sub(a)
+                .ok_or_else(|| overflow(TT::Years(a)))?;
             let mo = mom.month() as i64;
             let d = mom.day() as i64;
             let h = mom.hour() as i64;
@@ -1169,23 +1719,95 @@ fn sub_from_moment(mom: NaiveDateTime, tt: TimeType) -> Result<TimeType> {
 
             let (y, mo, d, h, mi, s) = adjust_times_sub(y, mo, d, h, mi, s);
 
-            let tt = NaiveDate::from_ymd_opt(y as i32, mo as u32, d as u32)
+            let tt = i32::try_from(y)
+                .ok()
+                .and_then(|y| NaiveDate::from_ymd_opt(y, mo as u32, d as u32))
                 .and_then(|nd| nd.and_hms_opt(h as u32, mi as u32, s as u32))
-                .ok_or(Error::OutOfBounds(
-                    y as i32, mo as u32, h as u32, h as u32, mi as u32, s as u32,
-                ))
-                .map_err(Error::from)?;
+                .ok_or(Error::OutOfRange(y, mo as u32, d as u32, h as u32, mi as u32, s as u32))?;
             Ok(TimeType::moment(tt))
         },
         TT::Moment(m) => Err(Error::CannotSub(TT::Moment(mom), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Moment(mom), TT::Date(d))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Moment(mom), TT::Time(t))),
         TT::EndOfYear(e) => Err(Error::CannotSub(TT::Moment(mom), TT::EndOfYear(e))),
         TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Moment(mom), TT::EndOfMonth(e))),
         TT::EndOfDay(e) => Err(Error::CannotSub(TT::Moment(mom), TT::EndOfDay(e))),
         TT::EndOfHour(e) => Err(Error::CannotSub(TT::Moment(mom), TT::EndOfHour(e))),
         TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Moment(mom), TT::EndOfMinute(e))),
-        TT::Addition(a, b) => sub_from_moment(mom, add(a, b)?),
-        TT::Subtraction(a, b) => sub_from_moment(mom, sub(a, b)?),
-    }
+        TT::Addition(a, b) => sub_from_moment(mom, add(a, b, trace)?, trace),
+        TT::Subtraction(a, b) => sub_from_moment(mom, sub(a, b, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromMoment, input, result)
+}
+
+fn sub_from_date(date: NaiveDate, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
+    use crate::timetype::TimeType as TT;
+
+    let input = trace.input(|| TT::date(date) - tt.clone());
+
+    let result = match tt {
+        TT::Days(a) => chrono::Duration::try_days(a)
+            .and_then(|delta| date.checked_sub_signed(delta))
+            .map(TT::date)
+            .ok_or(Error::Overflow(Operation::Subtract, TT::Date(date), TT::Days(a))),
+        amount @ TT::Months(_) | amount @ TT::Years(_) => {
+            // same rules as for moments, the time of day just does not matter
+            let midnight = date.and_time(NaiveTime::MIN);
+            match sub_from_moment(midnight, amount, &mut Trace::disabled())? {
+                TT::Moment(m) => Ok(TT::date(m.date())),
+                other => Err(Error::NotAMoment(other)),
+            }
+        },
+        TT::Time(t) => Err(Error::CannotSub(TT::Date(date), TT::Time(t))),
+        TT::Seconds(a) => Err(Error::CannotSub(TT::Date(date), TT::Seconds(a))),
+        TT::Minutes(a) => Err(Error::CannotSub(TT::Date(date), TT::Minutes(a))),
+        TT::Hours(a) => Err(Error::CannotSub(TT::Date(date), TT::Hours(a))),
+        TT::Moment(m) => Err(Error::CannotSub(TT::Date(date), TT::Moment(m))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Date(date), TT::Date(d))),
+        TT::EndOfYear(e) => Err(Error::CannotSub(TT::Date(date), TT::EndOfYear(e))),
+        TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Date(date), TT::EndOfMonth(e))),
+        TT::EndOfDay(e) => Err(Error::CannotSub(TT::Date(date), TT::EndOfDay(e))),
+        TT::EndOfHour(e) => Err(Error::CannotSub(TT::Date(date), TT::EndOfHour(e))),
+        TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Date(date), TT::EndOfMinute(e))),
+        TT::Addition(b, c) => sub_from_date(date, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_date(date, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromDate, input, result)
+}
+
+fn sub_from_time(time: NaiveTime, tt: TimeType, trace: &mut Trace) -> Result<TimeType> {
+    use crate::timetype::TimeType as TT;
+
+    let input = trace.input(|| TT::time(time) - tt.clone());
+
+    let result = match tt {
+        TT::Seconds(a) => Ok(TT::time(wrap_time(time, -i128::from(a)))),
+        TT::Minutes(a) => Ok(TT::time(wrap_time(time, -i128::from(a) * 60))),
+        TT::Hours(a) => Ok(TT::time(wrap_time(time, -i128::from(a) * 60 * 60))),
+        TT::Date(d) => Err(Error::CannotSub(TT::Time(time), TT::Date(d))),
+        TT::Days(a) => Err(Error::CannotSub(TT::Time(time), TT::Days(a))),
+        TT::Months(a) => Err(Error::CannotSub(TT::Time(time), TT::Months(a))),
+        TT::Years(a) => Err(Error::CannotSub(TT::Time(time), TT::Years(a))),
+        TT::Moment(m) => Err(Error::CannotSub(TT::Time(time), TT::Moment(m))),
+        TT::Time(t) => Err(Error::CannotSub(TT::Time(time), TT::Time(t))),
+        TT::EndOfYear(e) => Err(Error::CannotSub(TT::Time(time), TT::EndOfYear(e))),
+        TT::EndOfMonth(e) => Err(Error::CannotSub(TT::Time(time), TT::EndOfMonth(e))),
+        TT::EndOfDay(e) => Err(Error::CannotSub(TT::Time(time), TT::EndOfDay(e))),
+        TT::EndOfHour(e) => Err(Error::CannotSub(TT::Time(time), TT::EndOfHour(e))),
+        TT::EndOfMinute(e) => Err(Error::CannotSub(TT::Time(time), TT::EndOfMinute(e))),
+        TT::Addition(b, c) => sub_from_time(time, add(b, c, trace)?, trace),
+        TT::Subtraction(b, c) => sub_from_time(time, sub(b, c, trace)?, trace),
+    };
+
+    trace.record(Rule::SubFromTime, input, result)
+}
+
+// Move a time of day by `secs` seconds, wrapping around midnight
+fn wrap_time(time: NaiveTime, secs: i128) -> NaiveTime {
+    let secs = secs.rem_euclid(24 * 60 * 60) as i64;
+    time.overflowing_add_signed(chrono::Duration::seconds(secs)).0
 }
 
 #[cfg(test)]
@@ -1193,6 +1815,7 @@ mod tests {
     use chrono::NaiveDate;
 
     use crate::error::Error;
+    use crate::util::tests::moment_hms;
 
     use super::TimeType as TT;
 
@@ -1914,6 +2537,29 @@ mod tests {
 
         assert!(matches!(res, Error::CannotSub(..)));
     }
+
+    #[test]
+    fn test_sub_months_clamps_to_month_end() {
+        let result = (moment_hms(2024, 3, 31, 10, 0, 0) - TT::months(1)).calculate().unwrap();
+        assert_eq!(result, moment_hms(2024, 2, 29, 10, 0, 0));
+
+        let result = (moment_hms(2024, 5, 31, 10, 0, 0) - TT::months(1)).calculate().unwrap();
+        assert_eq!(result, moment_hms(2024, 4, 30, 10, 0, 0));
+
+        let result = (moment_hms(2024, 1, 30, 10, 0, 0) - TT::months(11))
+            .calculate()
+            .unwrap();
+        assert_eq!(result, moment_hms(2023, 2, 28, 10, 0, 0));
+    }
+
+    #[test]
+    fn test_sub_years_clamps_leap_day() {
+        let result = (moment_hms(2024, 2, 29, 10, 0, 0) - TT::years(1)).calculate().unwrap();
+        assert_eq!(result, moment_hms(2023, 2, 28, 10, 0, 0));
+
+        let result = (moment_hms(2024, 2, 29, 10, 0, 0) - TT::years(4)).calculate().unwrap();
+        assert_eq!(result, moment_hms(2020, 2, 29, 10, 0, 0));
+    }
 }
 
 #[cfg(test)]
@@ -2463,6 +3109,27 @@ mod test_time_adjustments {
         }
     }
 
+    #[test]
+    fn test_adjust_times_sub_month_border() {
+        generate_test_sub! {
+            y  : 2000 -  0 => 2000;
+            mo :    3 -  0 =>    2;
+            d  :    1 -  1 =>   29;
+            h  :    0 -  0 =>    0;
+            m  :    0 -  0 =>    0;
+            s  :    0 -  0 =>    0;
+        }
+
+        generate_test_sub! {
+            y  : 2001 -  0 => 2000;
+            mo :    1 -  0 =>   12;
+            d  :    1 -  0 =>   31;
+            h  :    0 -  0 =>   23;
+            m  :    0 -  0 =>   59;
+            s  :    0 - 60 =>    0;
+        }
+    }
+
     #[test]
     fn test_adjust_times_month_border() {
         generate_test_add! {
@@ -3609,148 +4276,383 @@ mod test_end_of_minute {
 
 #[cfg(test)]
 mod test_is_a {
-    use chrono::NaiveDate as ND;
-
     use crate::indicator::Day;
-
-    use super::TimeType as TT;
-
-    fn ymd(y: i32, m: u32, d: u32) -> TT {
-        TT::moment(
-            ND::from_ymd_opt(y, m, d)
-                .expect("Static time")
-                .and_hms_opt(0, 0, 0)
-                .expect("Static time"),
-        )
-    }
+    use crate::util::tests::moment;
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_1() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Monday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Monday).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_2() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Tuesday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Tuesday).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_3() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Wednesday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Wednesday).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_4() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Thursday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Thursday).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_5() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Friday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Friday).unwrap());
     }
 
     #[test]
     fn test_is_a_6() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Saturday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Saturday).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_a_7() {
-        assert!(ymd(2000, 1, 1).is_a(Day::Sunday).unwrap());
+        assert!(moment(2000, 1, 1).is_a(Day::Sunday).unwrap());
     }
 }
 
 #[cfg(test)]
 mod test_is_in {
-    use chrono::NaiveDate as ND;
-
     use crate::indicator::Month;
-
-    use super::TimeType as TT;
-
-    fn ymd(y: i32, m: u32, d: u32) -> TT {
-        TT::moment(
-            ND::from_ymd_opt(y, m, d)
-                .expect("Static time")
-                .and_hms_opt(0, 0, 0)
-                .expect("Static time"),
-        )
-    }
+    use crate::util::tests::moment;
 
     #[test]
     fn test_is_in_1() {
-        assert!(ymd(2000, 1, 1).is_in(Month::January).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::January).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_2() {
-        assert!(ymd(2000, 1, 1).is_in(Month::February).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::February).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_3() {
-        assert!(ymd(2000, 1, 1).is_in(Month::March).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::March).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_4() {
-        assert!(ymd(2000, 1, 1).is_in(Month::April).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::April).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_5() {
-        assert!(ymd(2000, 1, 1).is_in(Month::May).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::May).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_6() {
-        assert!(ymd(2000, 1, 1).is_in(Month::June).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::June).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_7() {
-        assert!(ymd(2000, 1, 1).is_in(Month::July).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::July).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_8() {
-        assert!(ymd(2000, 1, 1).is_in(Month::August).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::August).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_9() {
-        assert!(ymd(2000, 1, 1).is_in(Month::September).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::September).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_10() {
-        assert!(ymd(2000, 1, 1).is_in(Month::October).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::October).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_11() {
-        assert!(ymd(2000, 1, 1).is_in(Month::November).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::November).unwrap());
     }
 
     #[test]
     #[should_panic(expected = "assertion failed")]
     fn test_is_in_12() {
-        assert!(ymd(2000, 1, 1).is_in(Month::December).unwrap());
+        assert!(moment(2000, 1, 1).is_in(Month::December).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod test_timestamp {
+    use crate::util::tests::moment_hms;
+
+    use super::TimeType as TT;
+
+    #[test]
+    fn test_from_timestamp() {
+        assert_eq!(TT::from_timestamp(0).unwrap(), moment_hms(1970, 1, 1, 0, 0, 0));
+        assert_eq!(
+            TT::from_timestamp(1700000000).unwrap(),
+            moment_hms(2023, 11, 14, 22, 13, 20)
+        );
+        assert_eq!(TT::from_timestamp(-86400).unwrap(), moment_hms(1969, 12, 31, 0, 0, 0));
+        assert!(TT::from_timestamp(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_from_timestamp_millis() {
+        assert_eq!(
+            TT::from_timestamp_millis(1700000000000).unwrap(),
+            moment_hms(2023, 11, 14, 22, 13, 20)
+        );
+        assert!(TT::from_timestamp_millis(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_to_timestamp() {
+        assert_eq!(moment_hms(2023, 11, 14, 22, 13, 20).to_timestamp().unwrap(), 1700000000);
+        assert_eq!(
+            (moment_hms(2023, 11, 14, 22, 13, 20) + TT::seconds(10))
+                .to_timestamp()
+                .unwrap(),
+            1700000010
+        );
+        assert!(TT::days(1).to_timestamp().is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for ts in [-1_000_000_000, 0, 1, 1_234_567_890, 4_102_444_800] {
+            assert_eq!(TT::from_timestamp(ts).unwrap().to_timestamp().unwrap(), ts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_errors {
+    use crate::util::tests::moment_hms;
+
+    use crate::error::Error;
+    use crate::error::ErrorKind;
+    use crate::error::Operation;
+    use crate::timetype::TimeType as TT;
+
+    #[test]
+    fn test_cannot_add_carries_operands() {
+        let err = (TT::days(1) + moment_hms(2024, 1, 1, 12, 0, 0))
+            .calculate()
+            .unwrap_err();
+        assert_eq!(err, Error::CannotAdd(TT::days(1), moment_hms(2024, 1, 1, 12, 0, 0)));
+        assert_eq!(err.kind(), ErrorKind::Evaluation);
+        assert_eq!(err.code(), "evaluation/cannot-add");
+        assert_eq!(err.operation(), Some(Operation::Add));
+    }
+
+    #[test]
+    fn test_end_of_on_amount() {
+        for (tt, op) in [
+            (TT::days(1).end_of_year(), Operation::EndOfYear),
+            (TT::days(1).end_of_month(), Operation::EndOfMonth),
+            (TT::days(1).end_of_day(), Operation::EndOfDay),
+            (TT::days(1).end_of_hour(), Operation::EndOfHour),
+            (TT::days(1).end_of_minute(), Operation::EndOfMinute),
+        ] {
+            let err = tt.calculate().unwrap_err();
+            assert_eq!(err, Error::CannotCalculate(op, TT::days(1)));
+            assert_eq!(err.subexpression(), Some(TT::days(1)));
+        }
+    }
+
+    #[test]
+    fn test_not_a_moment_carries_subexpression() {
+        let err = TT::days(1).to_timestamp().unwrap_err();
+        assert_eq!(err, Error::NotAMoment(TT::days(1)));
+        assert_eq!(err.kind(), ErrorKind::Argument);
+    }
+
+    #[test]
+    fn test_amount_overflow() {
+        let err = (TT::years(i64::MAX) + TT::seconds(1)).calculate().unwrap_err();
+        assert_eq!(
+            err,
+            Error::Overflow(Operation::Add, TT::Years(i64::MAX), TT::Seconds(1))
+        );
+        assert_eq!(err.kind(), ErrorKind::Overflow);
+
+        let err = (TT::seconds(i64::MIN) - TT::seconds(1)).calculate().unwrap_err();
+        assert_eq!(err.operation(), Some(Operation::Subtract));
+        assert_eq!(err.kind(), ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_moment_overflow() {
+        let mom = (moment_hms(2024, 1, 1, 12, 0, 0) + TT::seconds(30))
+            .calculate()
+            .unwrap();
+
+        let err = (mom.clone() + TT::seconds(i64::MAX)).calculate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Overflow);
+        assert_eq!(err.subexpression(), Some(mom + TT::seconds(i64::MAX)));
+    }
+
+    #[test]
+    fn test_huge_amount_overflows_up_front() {
+        let mom = (moment_hms(2024, 1, 1, 12, 0, 0) + TT::seconds(30))
+            .calculate()
+            .unwrap();
+        let start = std::time::Instant::now();
+
+        let err = (mom.clone() + TT::days(10_000_000_000)).calculate().unwrap_err();
+        assert_eq!(
+            err,
+            Error::Overflow(Operation::Add, mom.clone(), TT::Days(10_000_000_000))
+        );
+
+        let err = (mom.clone() - TT::months(i64::MAX)).calculate().unwrap_err();
+        assert_eq!(err, Error::Overflow(Operation::Subtract, mom, TT::Months(i64::MAX)));
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_out_of_range_reports_the_day() {
+        let err = (moment_hms(262_142, 3, 15, 12, 0, 0) + TT::years(1))
+            .calculate()
+            .unwrap_err();
+        assert_eq!(err, Error::OutOfRange(262_143, 3, 15, 12, 0, 0));
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+
+        let err = (moment_hms(-262_143, 3, 15, 12, 0, 0) - TT::years(1))
+            .calculate()
+            .unwrap_err();
+        assert_eq!(err, Error::OutOfRange(-262_144, 3, 15, 12, 0, 0));
+    }
+}
+
+#[cfg(test)]
+mod test_date_time {
+    use chrono::NaiveDate;
+    use chrono::NaiveTime;
+
+    use crate::error::Error;
+    use crate::indicator::Day;
+    use crate::timetype::TimeType as TT;
+
+    fn date(y: i32, m: u32, d: u32) -> TT {
+        TT::date(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+    }
+
+    fn time(h: u32, m: u32, s: u32) -> TT {
+        TT::time(NaiveTime::from_hms_opt(h, m, s).unwrap())
+    }
+
+    fn moment(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> TT {
+        TT::moment(NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, mi, s).unwrap())
+    }
+
+    #[test]
+    fn test_date_plus_time_is_moment() {
+        assert_eq!(
+            (date(2024, 3, 10) + time(14, 30, 0)).calculate().unwrap(),
+            moment(2024, 3, 10, 14, 30, 0)
+        );
+        assert_eq!(
+            (time(14, 30, 0) + date(2024, 3, 10)).calculate().unwrap(),
+            moment(2024, 3, 10, 14, 30, 0)
+        );
+    }
+
+    #[test]
+    fn test_date_is_not_midnight() {
+        assert_ne!(date(2024, 3, 10), moment(2024, 3, 10, 0, 0, 0));
+        assert_eq!(date(2024, 3, 10).calculate().unwrap(), date(2024, 3, 10));
+    }
+
+    #[test]
+    fn test_date_arithmetic() {
+        assert_eq!((date(2024, 2, 28) + TT::days(2)).calculate().unwrap(), date(2024, 3, 1));
+        assert_eq!((date(2024, 3, 1) - TT::days(1)).calculate().unwrap(), date(2024, 2, 29));
+        assert_eq!(
+            (date(2024, 1, 15) + TT::months(1)).calculate().unwrap(),
+            date(2024, 2, 15)
+        );
+        assert_eq!(
+            (date(2024, 1, 15) - TT::years(1)).calculate().unwrap(),
+            date(2023, 1, 15)
+        );
+        assert_eq!(
+            (date(2024, 1, 1) + TT::weeks(1) + time(9, 0, 0)).calculate().unwrap(),
+            moment(2024, 1, 8, 9, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_date_with_sub_day_amount_fails() {
+        assert_eq!(
+            (date(2024, 1, 1) + TT::hours(1)).calculate(),
+            Err(Error::CannotAdd(date(2024, 1, 1), TT::hours(1)))
+        );
+        assert!((date(2024, 1, 1) - TT::minutes(1)).calculate().is_err());
+        assert!((date(2024, 1, 1) - time(1, 0, 0)).calculate().is_err());
+    }
+
+    #[test]
+    fn test_time_wraps_around_midnight() {
+        assert_eq!((time(23, 30, 0) + TT::hours(2)).calculate().unwrap(), time(1, 30, 0));
+        assert_eq!((time(0, 15, 0) - TT::minutes(30)).calculate().unwrap(), time(23, 45, 0));
+        assert_eq!((time(12, 0, 0) + TT::hours(48)).calculate().unwrap(), time(12, 0, 0));
+        assert_eq!(
+            (time(12, 0, 0) - TT::seconds(i64::MIN)).calculate().unwrap(),
+            time(3, 30, 8)
+        );
+    }
+
+    #[test]
+    fn test_time_with_days_fails() {
+        assert!((time(12, 0, 0) + TT::days(1)).calculate().is_err());
+        assert!((TT::hours(1) + time(12, 0, 0)).calculate().is_err());
+        assert!((moment(2024, 1, 1, 0, 0, 0) + time(12, 0, 0)).calculate().is_err());
+    }
+
+    #[test]
+    fn test_end_of() {
+        assert_eq!(date(2024, 2, 10).end_of_month().calculate().unwrap(), date(2024, 2, 29));
+        assert_eq!(date(2024, 2, 10).end_of_year().calculate().unwrap(), date(2024, 12, 31));
+        assert!(date(2024, 2, 10).end_of_day().calculate().is_err());
+
+        assert_eq!(time(14, 30, 10).end_of_hour().calculate().unwrap(), time(14, 59, 59));
+        assert_eq!(time(14, 30, 10).end_of_minute().calculate().unwrap(), time(14, 30, 59));
+        assert_eq!(time(14, 30, 10).end_of_day().calculate().unwrap(), time(23, 59, 59));
+        assert!(time(14, 30, 10).end_of_year().calculate().is_err());
+    }
+
+    #[test]
+    fn test_date_indicators() {
+        assert!(date(2024, 3, 10).is_a(Day::Sunday).unwrap());
+        assert!(time(12, 0, 0).is_a(Day::Sunday).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            (date(2024, 3, 10) + time(14, 30, 0)).to_string(),
+            "2024-03-10 + 14:30:00"
+        );
     }
 }
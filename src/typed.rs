@@ -0,0 +1,352 @@
+//! Strongly typed wrappers around `TimeType`
+//!
+//! A `TimeType` can hold points in time as well as lengths of time, so combining them wrongly
+//! (like adding a moment to an amount) is only detected when calculating. `Moment` and `Amount`
+//! only allow the valid combinations at compile time:
+//!
+//! * `Moment + Amount = Moment`, `Moment - Amount = Moment`
+//! * `Amount + Amount = Amount`, `Amount - Amount = Amount`
+//!
+//! Both are still lazy expressions and can be converted to and from `TimeType` without losing
+//! anything.
+
+use std::fmt;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+use chrono::NaiveDateTime;
+
+use crate::difference::Difference;
+use crate::difference::Unit;
+use crate::error::Error;
+use crate::error::Result;
+use crate::timetype::TimeType;
+
+/// A point in time, which is a `TimeType` evaluating to a `TimeType::Moment`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Moment(TimeType);
+
+/// A length of time, which is a `TimeType` evaluating to an amount
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Amount(TimeType);
+
+impl Moment {
+    pub fn new(ndt: NaiveDateTime) -> Moment {
+        Moment(TimeType::moment(ndt))
+    }
+
+    /// Alias for `Moment::new(chrono::offset::Local::now().naive_local())`
+    pub fn today() -> Moment {
+        Moment(TimeType::today())
+    }
+
+    /// See `TimeType::end_of_year()`
+    pub fn end_of_year(self) -> Moment {
+        Moment(self.0.end_of_year())
+    }
+
+    /// See `TimeType::end_of_month()`
+    pub fn end_of_month(self) -> Moment {
+        Moment(self.0.end_of_month())
+    }
+
+    /// See `TimeType::end_of_day()`
+    pub fn end_of_day(self) -> Moment {
+        Moment(self.0.end_of_day())
+    }
+
+    /// See `TimeType::end_of_hour()`
+    pub fn end_of_hour(self) -> Moment {
+        Moment(self.0.end_of_hour())
+    }
+
+    /// See `TimeType::end_of_minute()`
+    pub fn end_of_minute(self) -> Moment {
+        Moment(self.0.end_of_minute())
+    }
+
+    /// See `TimeType::difference()`
+    pub fn difference(&self, other: &Moment, units: &[Unit]) -> Result<Difference> {
+        self.0.difference(&other.0, units)
+    }
+
+    pub fn as_timetype(&self) -> &TimeType {
+        &self.0
+    }
+
+    pub fn into_timetype(self) -> TimeType {
+        self.0
+    }
+
+    /// Calculate the expression
+    ///
+    /// This can only fail if the result overflows or is out of range, never because of mismatched
+    /// operands.
+    pub fn calculate(self) -> Result<NaiveDateTime> {
+        self.0.calculate().and_then(|tt| match tt {
+            TimeType::Moment(ndt) => Ok(ndt),
+            other => Err(Error::NotAMoment(other)),
+        })
+    }
+}
+
+impl Amount {
+    pub fn seconds(i: i64) -> Amount {
+        Amount(TimeType::seconds(i))
+    }
+
+    pub fn minutes(i: i64) -> Amount {
+        Amount(TimeType::minutes(i))
+    }
+
+    pub fn hours(i: i64) -> Amount {
+        Amount(TimeType::hours(i))
+    }
+
+    pub fn days(i: i64) -> Amount {
+        Amount(TimeType::days(i))
+    }
+
+    /// Helper for `Amount::days(i * 7)`
+    pub fn weeks(i: i64) -> Amount {
+        Amount(TimeType::weeks(i))
+    }
+
+    pub fn months(i: i64) -> Amount {
+        Amount(TimeType::months(i))
+    }
+
+    pub fn years(i: i64) -> Amount {
+        Amount(TimeType::years(i))
+    }
+
+    pub fn as_timetype(&self) -> &TimeType {
+        &self.0
+    }
+
+    pub fn into_timetype(self) -> TimeType {
+        self.0
+    }
+
+    /// Calculate the expression into a single amount
+    ///
+    /// This can only fail if the result overflows, never because of mismatched operands.
+    pub fn calculate(self) -> Result<Amount> {
+        self.0.calculate().map(Amount)
+    }
+}
+
+macro_rules! impl_operators {
+    ($lhs:ident, $rhs:ident) => {
+        impl Add<$rhs> for $lhs {
+            type Output = $lhs;
+
+            fn add(self, rhs: $rhs) -> Self::Output {
+                $lhs(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign<$rhs> for $lhs {
+            fn add_assign(&mut self, rhs: $rhs) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub<$rhs> for $lhs {
+            type Output = $lhs;
+
+            fn sub(self, rhs: $rhs) -> Self::Output {
+                $lhs(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign<$rhs> for $lhs {
+            fn sub_assign(&mut self, rhs: $rhs) {
+                self.0 -= rhs.0;
+            }
+        }
+    };
+}
+
+impl_operators!(Moment, Amount);
+impl_operators!(Amount, Amount);
+
+impl fmt::Display for Moment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<NaiveDateTime> for Moment {
+    fn from(ndt: NaiveDateTime) -> Moment {
+        Moment::new(ndt)
+    }
+}
+
+impl From<Moment> for TimeType {
+    fn from(m: Moment) -> TimeType {
+        m.0
+    }
+}
+
+impl From<Amount> for TimeType {
+    fn from(a: Amount) -> TimeType {
+        a.0
+    }
+}
+
+/// Fails with `Error::NotAMoment` if the expression does not evaluate to a moment
+///
+/// The expression is checked without calculating it.
+impl TryFrom<TimeType> for Moment {
+    type Error = Error;
+
+    fn try_from(tt: TimeType) -> Result<Moment> {
+        match kind_of(&tt) {
+            Some(Kind::Moment) => Ok(Moment(tt)),
+            _ => Err(Error::NotAMoment(tt)),
+        }
+    }
+}
+
+/// Fails with `Error::NotAnAmount` if the expression does not evaluate to an amount
+///
+/// The expression is checked without calculating it.
+impl TryFrom<TimeType> for Amount {
+    type Error = Error;
+
+    fn try_from(tt: TimeType) -> Result<Amount> {
+        match kind_of(&tt) {
+            Some(Kind::Amount) => Ok(Amount(tt)),
+            _ => Err(Error::NotAnAmount(tt)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Moment,
+    Date,
+    Time,
+    Amount,
+}
+
+// What an expression evaluates to, or None if calculating it fails because of its operands
+fn kind_of(tt: &TimeType) -> Option<Kind> {
+    use crate::timetype::TimeType as TT;
+
+    match tt {
+        TT::Seconds(_) | TT::Minutes(_) | TT::Hours(_) | TT::Days(_) | TT::Months(_) | TT::Years(_) => {
+            Some(Kind::Amount)
+        },
+        TT::Moment(_) => Some(Kind::Moment),
+        TT::Date(_) => Some(Kind::Date),
+        TT::Time(_) => Some(Kind::Time),
+        TT::Addition(a, b) => match (kind_of(a)?, kind_of(b)?) {
+            (Kind::Date, Kind::Time) | (Kind::Time, Kind::Date) => Some(Kind::Moment),
+            (k, Kind::Amount) => Some(k),
+            _ => None,
+        },
+        TT::Subtraction(a, b) => match (kind_of(a)?, kind_of(b)?) {
+            (k, Kind::Amount) => Some(k),
+            _ => None,
+        },
+        TT::EndOfYear(e) | TT::EndOfMonth(e) => match kind_of(e)? {
+            k @ Kind::Moment | k @ Kind::Date => Some(k),
+            _ => None,
+        },
+        TT::EndOfDay(e) | TT::EndOfHour(e) | TT::EndOfMinute(e) => match kind_of(e)? {
+            k @ Kind::Moment | k @ Kind::Time => Some(k),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::tests::ymd;
+
+    use super::*;
+
+    #[test]
+    fn test_moment_plus_amount() {
+        let m = Moment::new(ymd(2024, 1, 31)) + (Amount::months(1) - Amount::days(1));
+        assert_eq!(m.calculate().unwrap(), ymd(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_assign_operators() {
+        let mut m = Moment::new(ymd(2024, 1, 1));
+        m += Amount::weeks(2);
+        m -= Amount::days(1);
+        assert_eq!(m.calculate().unwrap(), ymd(2024, 1, 14));
+
+        let mut a = Amount::days(1);
+        a += Amount::hours(12);
+        assert_eq!(a.calculate().unwrap(), Amount::hours(36));
+    }
+
+    #[test]
+    fn test_end_of_month() {
+        let m = (Moment::new(ymd(2024, 1, 15)) + Amount::months(1)).end_of_month();
+        assert_eq!(m.calculate().unwrap(), ymd(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_roundtrip_timetype() {
+        let m = Moment::new(ymd(2024, 1, 15)) + Amount::days(3);
+        let tt: TimeType = m.clone().into();
+        assert_eq!(tt, TimeType::moment(ymd(2024, 1, 15)) + TimeType::days(3));
+        assert_eq!(Moment::try_from(tt).unwrap(), m);
+
+        let a = Amount::days(3) - Amount::hours(1);
+        let tt: TimeType = a.clone().into();
+        assert_eq!(Amount::try_from(tt).unwrap(), a);
+    }
+
+    #[test]
+    fn test_try_from_rejects_mismatch() {
+        let tt = TimeType::days(1) + TimeType::moment(ymd(2024, 1, 1));
+        assert_eq!(Moment::try_from(tt.clone()), Err(Error::NotAMoment(tt.clone())));
+        assert_eq!(Amount::try_from(tt.clone()), Err(Error::NotAnAmount(tt)));
+
+        assert!(Moment::try_from(TimeType::days(1)).is_err());
+        assert!(Amount::try_from(TimeType::moment(ymd(2024, 1, 1))).is_err());
+        assert!(Moment::try_from(TimeType::days(1).end_of_year()).is_err());
+        assert!(Moment::try_from(TimeType::moment(ymd(2024, 1, 1)) - TimeType::moment(ymd(2024, 1, 1))).is_err());
+        assert!(Moment::try_from(TimeType::date(ymd(2024, 1, 1).date())).is_err());
+    }
+
+    #[test]
+    fn test_try_from_date_plus_time() {
+        let tt = TimeType::date(ymd(2024, 1, 1).date())
+            + TimeType::time(chrono::NaiveTime::from_hms_opt(14, 30, 0).unwrap());
+        let m = Moment::try_from(tt).unwrap();
+        assert_eq!(
+            m.calculate().unwrap(),
+            ymd(2024, 1, 1) + chrono::Duration::minutes(14 * 60 + 30)
+        );
+    }
+
+    #[test]
+    fn test_difference() {
+        let from = Moment::new(ymd(2021, 1, 5));
+        let to = Moment::new(ymd(2024, 3, 16));
+        let d = from.difference(&to, Unit::YMD).unwrap();
+        assert_eq!((d.years(), d.months(), d.days()), (3, 2, 11));
+    }
+
+    #[test]
+    fn test_display() {
+        let m = Moment::new(ymd(2024, 1, 31)) + Amount::months(1);
+        assert_eq!(m.to_string(), "2024-01-31 00:00:00 + 1 month");
+    }
+}
@@ -1,3 +1,15 @@
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::timetype::TimeType;
+
+/// If no occurrence was found for this many days, a schedule is considered to never match
+pub const MAX_DAYS_WITHOUT_OCCURRENCE: i64 = 400 * 366;
+
 #[inline]
 pub fn adjust_times_add(
     mut y: i64,
@@ -46,34 +58,55 @@ pub fn adjust_times_sub(
     mut mi: i64,
     mut s: i64,
 ) -> (i64, i64, i64, i64, i64, i64) {
-    if s < 0 {
-        mi -= (s.abs() / 60) + 1;
-        s = 60 - (0 - s).abs();
+    // Borrow from the next bigger unit as long as the value is negative
+    macro_rules! fix {
+        {
+            $base:ident,
+            $border:expr,
+            $next:ident
+        } => {
+            $next += $base.div_euclid($border);
+            $base = $base.rem_euclid($border);
+        }
     }
 
-    if mi < 0 {
-        h -= (mi.abs() / 60) + 1;
-        mi = 60 - (0 - mi).abs();
-    }
+    fix! { s , 60, mi }
+    fix! { mi, 60, h  }
+    fix! { h , 24, d  }
 
-    if h < 0 {
-        d -= (h.abs() / 24) + 1;
-        h = 24 - (0 - h).abs();
-    }
+    y += (mo - 1).div_euclid(12);
+    mo = (mo - 1).rem_euclid(12) + 1;
 
-    if d < 1 {
-        mo -= (d.abs() / 32) + 1;
-        d = 31 - (0 - d).abs();
-    }
+    // Subtracting months or years can land on a shorter month
+    d = d.min(get_num_of_days_in_month(y, mo));
 
-    if mo < 1 {
-        y -= (mo.abs() / 13) + 1;
-        mo = 12 - (0 - mo).abs();
+    while d < 1 {
+        mo -= 1;
+        if mo < 1 {
+            y -= 1;
+            mo = 12;
+        }
+        d += get_num_of_days_in_month(y, mo);
     }
 
     (y, mo, d, h, mi, s)
 }
 
+/// Add `months` to `moment`, clamping the day to the last day of the resulting month
+pub fn add_months_clamped(moment: &NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let total = i64::from(moment.year())
+        .checked_mul(12)?
+        .checked_add(i64::from(moment.month0()))?
+        .checked_add(months)?;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+    let day = moment
+        .day()
+        .min(get_num_of_days_in_month(year.into(), month.into()) as u32);
+
+    NaiveDate::from_ymd_opt(year, month, day).map(|date| date.and_time(moment.time()))
+}
+
 #[inline]
 pub fn get_num_of_days_in_month(y: i64, m: i64) -> i64 {
     if m == 1 || m == 3 || m == 5 || m == 7 || m == 8 || m == 10 || m == 12 {
@@ -94,6 +127,68 @@ pub fn is_leap_year(y: i32) -> bool {
     (y % 4 == 0) && (y % 100 != 0 || y % 400 == 0)
 }
 
+pub fn days_in_month(date: NaiveDate) -> u32 {
+    get_num_of_days_in_month(date.year() as i64, date.month() as i64) as u32
+}
+
+pub fn parse_error<S: Into<String>>(position: usize, message: S) -> Error {
+    Error::Parse {
+        position,
+        message: message.into(),
+    }
+}
+
+/// Split `s` at whitespace, keeping the byte offset of each token
+pub fn split_tokens(s: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+        match (c.is_whitespace(), start) {
+            (true, Some(st)) => {
+                tokens.push((st, &s[st..i]));
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => {},
+        }
+    }
+
+    tokens
+}
+
+/// Something which can search its next match, like a cron expression
+pub trait Schedule {
+    /// The first match at or after `moment`, None if there is none within the search limit
+    fn next_from(&self, moment: &NaiveDateTime) -> Option<NaiveDateTime>;
+}
+
+/// The iterator over the matches of a `Schedule`, each searched from right after the previous one
+#[derive(Debug)]
+pub struct ScheduleIter<S> {
+    schedule: S,
+    next: Option<NaiveDateTime>,
+}
+
+impl<S> ScheduleIter<S> {
+    pub fn new(schedule: S, start: NaiveDateTime) -> ScheduleIter<S> {
+        ScheduleIter {
+            schedule,
+            next: Some(start),
+        }
+    }
+}
+
+impl<S: Schedule> Iterator for ScheduleIter<S> {
+    type Item = Result<TimeType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let found = self.schedule.next_from(&self.next?);
+        self.next = found.and_then(|m| m.checked_add_signed(Duration::seconds(1)));
+        found.map(|m| Ok(TimeType::moment(m)))
+    }
+}
+
 #[test]
 fn test_is_leap_year() {
     let leaps = [
@@ -181,3 +276,28 @@ fn test_get_num_of_days_in_month() {
     assert_eq!(30, get_num_of_days_in_month(2004, 11));
     assert_eq!(31, get_num_of_days_in_month(2004, 12));
 }
+
+/// Fixtures shared by the tests of all modules
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+    use chrono::NaiveDateTime;
+
+    use crate::timetype::TimeType;
+
+    pub fn ymd_hms(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, mi, s).unwrap()
+    }
+
+    pub fn ymd(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        ymd_hms(y, m, d, 0, 0, 0)
+    }
+
+    pub fn moment_hms(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> TimeType {
+        TimeType::moment(ymd_hms(y, m, d, h, mi, s))
+    }
+
+    pub fn moment(y: i32, m: u32, d: u32) -> TimeType {
+        moment_hms(y, m, d, 0, 0, 0)
+    }
+}